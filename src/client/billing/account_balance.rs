@@ -0,0 +1,42 @@
+use rust_decimal::Decimal;
+use serde::{Deserialize, Deserializer, Serialize};
+use std::str::FromStr;
+
+/// The balance of the account, as returned by `QueryAccountBalance`.
+///
+/// Aliyun formats the amounts with thousands separators (`"10,000.00"`); they are parsed
+/// into exact decimals.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+#[serde(rename_all = "PascalCase")]
+pub struct AccountBalance {
+    #[serde(deserialize_with = "deserialize_amount")]
+    pub available_amount: Decimal,
+    #[serde(deserialize_with = "deserialize_amount")]
+    pub available_cash_amount: Decimal,
+    #[serde(deserialize_with = "deserialize_amount")]
+    pub credit_amount: Decimal,
+    #[serde(default, deserialize_with = "deserialize_optional_amount")]
+    pub mybank_credit_amount: Option<Decimal>,
+    #[serde(default, deserialize_with = "deserialize_optional_amount")]
+    pub quota_limit: Option<Decimal>,
+    /// The currency of the amounts, e.g. `CNY` or `USD`.
+    pub currency: String,
+}
+
+fn parse_amount<E: serde::de::Error>(amount: &str) -> Result<Decimal, E> {
+    Decimal::from_str(&amount.trim().replace(',', ""))
+        .map_err(|e| E::custom(format!("invalid amount '{}': {}", amount, e)))
+}
+
+fn deserialize_amount<'de, D: Deserializer<'de>>(deserializer: D) -> Result<Decimal, D::Error> {
+    parse_amount(&String::deserialize(deserializer)?)
+}
+
+fn deserialize_optional_amount<'de, D: Deserializer<'de>>(
+    deserializer: D,
+) -> Result<Option<Decimal>, D::Error> {
+    match Option::<String>::deserialize(deserializer)? {
+        Some(amount) if !amount.trim().is_empty() => parse_amount(&amount).map(Some),
+        _ => Ok(None),
+    }
+}
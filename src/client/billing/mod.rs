@@ -0,0 +1,168 @@
+pub mod account_balance;
+
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    client::{
+        billing::account_balance::AccountBalance,
+        error::{AdvancedClientError, AliyunRejection},
+        utils::parse_json_value,
+        AliyunClient,
+    },
+    services::billing::query_account_balance,
+};
+
+/// The envelope of the Billing (BSS OpenAPI) responses.
+///
+/// Billing reports failures with `Success: false` and its own `Code`, instead of the
+/// usual Aliyun error document.
+#[derive(Serialize, Deserialize, Debug)]
+#[serde(rename_all = "PascalCase")]
+pub struct BillingResponse<T> {
+    pub request_id: String,
+    pub success: bool,
+    pub code: String,
+    pub message: Option<String>,
+    pub data: Option<T>,
+}
+
+impl<T> BillingResponse<T> {
+    /// Returns the data of a successful response, or an `AliyunRejectError` built from the
+    /// code and message of a failed one.
+    pub fn into_result(self) -> Result<T, AdvancedClientError> {
+        match self.data {
+            Some(data) if self.success => Ok(data),
+            _ => Err(AdvancedClientError::AliyunRejectError(AliyunRejection {
+                code: self.code,
+                host_id: String::new(),
+                message: self.message.unwrap_or_default(),
+                request_id: self.request_id,
+                recommend: String::new(),
+            })),
+        }
+    }
+}
+
+/// Parses a Billing response, including the failures answered with an error status.
+fn parse_billing_response<T>(
+    response: Result<serde_json::Value, AdvancedClientError>,
+) -> Result<T, AdvancedClientError>
+where
+    T: std::fmt::Debug + for<'de> Deserialize<'de>,
+{
+    match response {
+        Ok(value) => parse_json_value::<BillingResponse<T>>(value)?.into_result(),
+        Err(AdvancedClientError::HttpStatus { status, body }) => {
+            match serde_json::from_str::<BillingResponse<T>>(&body) {
+                Ok(envelope) => envelope.into_result(),
+                Err(_) => Err(AdvancedClientError::HttpStatus { status, body }),
+            }
+        }
+        Err(e) => Err(e),
+    }
+}
+
+/// A thin, non-owning client for Billing operations, mirroring `STSClient`.
+pub struct BillingClient<'a> {
+    pub client: &'a AliyunClient,
+}
+
+impl<'a> BillingClient<'a> {
+    /// Create a new `BillingClient` that borrows the provided `AliyunClient`.
+    pub fn new(client: &'a AliyunClient) -> Self {
+        Self { client }
+    }
+
+    /// Call the Billing `QueryAccountBalance` operation and return the balance of the
+    /// account.
+    ///
+    /// A response with `Success: false` is returned as an `AliyunRejectError`.
+    pub async fn query_account_balance(&self) -> Result<AccountBalance, AdvancedClientError> {
+        parse_billing_response(query_account_balance(self.client).await)
+    }
+}
+
+impl AliyunClient {
+    /// Obtain a `BillingClient` that borrows this `AliyunClient`.
+    pub fn billing<'a>(&'a self) -> BillingClient<'a> {
+        BillingClient { client: self }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rust_decimal::Decimal;
+    use serde_json::json;
+
+    #[test]
+    fn test_parse_account_balance() {
+        let balance = parse_billing_response::<AccountBalance>(Ok(json!({
+            "Code": "200",
+            "Message": "Successful!",
+            "RequestId": "REQ",
+            "Success": true,
+            "Data": {
+                "AvailableCashAmount": "12,345.67",
+                "MybankCreditAmount": "0.00",
+                "Currency": "CNY",
+                "AvailableAmount": "1,012,345.67",
+                "CreditAmount": "1,000,000.00",
+                "QuotaLimit": ""
+            }
+        })))
+        .unwrap();
+        assert_eq!(balance.available_amount, Decimal::new(101234567, 2));
+        assert_eq!(balance.available_cash_amount, Decimal::new(1234567, 2));
+        assert_eq!(balance.credit_amount, Decimal::new(100000000, 2));
+        assert_eq!(balance.mybank_credit_amount, Some(Decimal::ZERO));
+        assert_eq!(balance.quota_limit, None);
+        assert_eq!(balance.currency, "CNY");
+    }
+
+    #[test]
+    fn test_unsuccessful_response_is_an_error() {
+        let result = parse_billing_response::<AccountBalance>(Ok(json!({
+            "Code": "NotApplicable",
+            "Message": "This API is not applicable for caller.",
+            "RequestId": "REQ",
+            "Success": false
+        })));
+        assert!(matches!(
+            result,
+            Err(AdvancedClientError::AliyunRejectError(rejection))
+                if rejection.code == "NotApplicable" && rejection.request_id == "REQ"
+        ));
+
+        let result = parse_billing_response::<AccountBalance>(Err(
+            AdvancedClientError::HttpStatus {
+                status: 400,
+                body: r#"{"Code":"InvalidParameter","Message":"Bad","RequestId":"REQ","Success":false}"#
+                    .to_string(),
+            },
+        ));
+        assert!(matches!(
+            result,
+            Err(AdvancedClientError::AliyunRejectError(rejection)) if rejection.code == "InvalidParameter"
+        ));
+    }
+
+    #[test]
+    fn test_invalid_amount() {
+        let result = parse_billing_response::<AccountBalance>(Ok(json!({
+            "Code": "200",
+            "RequestId": "REQ",
+            "Success": true,
+            "Data": {
+                "AvailableCashAmount": "N/A",
+                "Currency": "CNY",
+                "AvailableAmount": "0.00",
+                "CreditAmount": "0.00"
+            }
+        })));
+        assert!(matches!(
+            result,
+            Err(AdvancedClientError::ResultDeserializationError { .. })
+        ));
+    }
+}
@@ -0,0 +1,330 @@
+use std::{sync::Arc, time::Duration};
+
+use crate::client::{
+    clock::Clock,
+    credentials::{
+        Credentials, CredentialsProvider, DefaultCredentialsChain, StaticCredentialsProvider,
+    },
+    endpoint::{DefaultEndpointResolver, EndpointResolver, EndpointType},
+    error::AdvancedClientError,
+    retry::RetryPolicy,
+    transport::{HttpTransport, ReqwestTransport},
+    AliyunClient,
+};
+use crate::signing::SignatureVersion;
+
+/// The `User-Agent` sent when none is configured.
+pub const DEFAULT_USER_AGENT: &str = concat!("alibabacloud-rust/", env!("CARGO_PKG_VERSION"));
+/// The default time allowed to establish a connection.
+pub const DEFAULT_CONNECT_TIMEOUT: Duration = Duration::from_secs(5);
+/// The default time allowed between two reads of a response.
+pub const DEFAULT_READ_TIMEOUT: Duration = Duration::from_secs(10);
+
+/// Configures and creates an `AliyunClient`, see `AliyunClient::builder`.
+///
+/// ```ignore
+/// let client = AliyunClient::builder()
+///     .credentials(access_key_id, access_key_secret)
+///     .proxy(reqwest::Proxy::all("http://proxy.internal:3128")?)
+///     .timeout(Duration::from_secs(30))
+///     .default_region("cn-hangzhou")
+///     .endpoint_type(EndpointType::Vpc)
+///     .build()?;
+/// ```
+pub struct AliyunClientBuilder {
+    credentials_provider: Option<Arc<dyn CredentialsProvider>>,
+    http_client: Option<reqwest::Client>,
+    transport: Option<Arc<dyn HttpTransport>>,
+    connect_timeout: Duration,
+    read_timeout: Duration,
+    timeout: Option<Duration>,
+    proxy: Option<reqwest::Proxy>,
+    root_certificates: Vec<reqwest::Certificate>,
+    user_agent: String,
+    default_region: Option<String>,
+    default_endpoint_resolver: DefaultEndpointResolver,
+    endpoint_resolver: Option<Arc<dyn EndpointResolver>>,
+    signature_version: SignatureVersion,
+    retry_policy: RetryPolicy,
+}
+
+impl Default for AliyunClientBuilder {
+    fn default() -> Self {
+        Self {
+            credentials_provider: None,
+            http_client: None,
+            transport: None,
+            connect_timeout: DEFAULT_CONNECT_TIMEOUT,
+            read_timeout: DEFAULT_READ_TIMEOUT,
+            timeout: None,
+            proxy: None,
+            root_certificates: Vec::new(),
+            user_agent: DEFAULT_USER_AGENT.to_string(),
+            default_region: None,
+            default_endpoint_resolver: DefaultEndpointResolver::default(),
+            endpoint_resolver: None,
+            signature_version: SignatureVersion::default(),
+            retry_policy: RetryPolicy::default(),
+        }
+    }
+}
+
+impl AliyunClientBuilder {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Signs the requests with the given access key.
+    pub fn credentials(self, access_key_id: String, access_key_secret: String) -> Self {
+        self.credentials_provider(StaticCredentialsProvider::new(Credentials::new(
+            access_key_id,
+            access_key_secret,
+        )))
+    }
+
+    /// Asks the given provider for credentials before every request. Defaults to the
+    /// `DefaultCredentialsChain`.
+    pub fn credentials_provider(mut self, provider: impl CredentialsProvider + 'static) -> Self {
+        self.credentials_provider = Some(Arc::new(provider));
+        self
+    }
+
+    /// Sets the time allowed to establish a connection (`DEFAULT_CONNECT_TIMEOUT` by default).
+    pub fn connect_timeout(mut self, timeout: Duration) -> Self {
+        self.connect_timeout = timeout;
+        self
+    }
+
+    /// Sets the time allowed between two reads of a response (`DEFAULT_READ_TIMEOUT` by default).
+    pub fn read_timeout(mut self, timeout: Duration) -> Self {
+        self.read_timeout = timeout;
+        self
+    }
+
+    /// Sets the time allowed for a whole request, from connecting until the response body
+    /// is read. There is no overall limit by default.
+    pub fn timeout(mut self, timeout: Duration) -> Self {
+        self.timeout = Some(timeout);
+        self
+    }
+
+    /// Sends every request through the given HTTP(S) proxy.
+    ///
+    /// Without a proxy the `HTTP_PROXY` / `HTTPS_PROXY` environment variables are honoured.
+    pub fn proxy(mut self, proxy: reqwest::Proxy) -> Self {
+        self.proxy = Some(proxy);
+        self
+    }
+
+    /// Trusts an additional root certificate, e.g. the CA of a TLS-intercepting proxy.
+    pub fn add_root_certificate(mut self, certificate: reqwest::Certificate) -> Self {
+        self.root_certificates.push(certificate);
+        self
+    }
+
+    /// Sets the `User-Agent` header (`DEFAULT_USER_AGENT` by default).
+    pub fn user_agent(mut self, user_agent: impl Into<String>) -> Self {
+        self.user_agent = user_agent.into();
+        self
+    }
+
+    /// Sets the region used by operations when none is given explicitly.
+    pub fn default_region(mut self, region: impl Into<String>) -> Self {
+        self.default_region = Some(region.into());
+        self
+    }
+
+    /// Uses the public or VPC endpoints (`EndpointType::Public` by default).
+    pub fn endpoint_type(mut self, endpoint_type: EndpointType) -> Self {
+        self.default_endpoint_resolver = self
+            .default_endpoint_resolver
+            .with_endpoint_type(endpoint_type);
+        self
+    }
+
+    /// Sends the requests of `product` (e.g. "ecs") to `endpoint` in every region.
+    pub fn endpoint_override(
+        mut self,
+        product: impl Into<String>,
+        endpoint: impl Into<String>,
+    ) -> Self {
+        self.default_endpoint_resolver = self
+            .default_endpoint_resolver
+            .with_override(product, None, endpoint);
+        self
+    }
+
+    /// Sends the requests of `product` in `region_id` to `endpoint`, e.g. for finance and
+    /// gov cloud regions.
+    pub fn region_endpoint_override(
+        mut self,
+        product: impl Into<String>,
+        region_id: &str,
+        endpoint: impl Into<String>,
+    ) -> Self {
+        self.default_endpoint_resolver =
+            self.default_endpoint_resolver
+                .with_override(product, Some(region_id), endpoint);
+        self
+    }
+
+    /// Resolves the endpoints with a custom resolver instead of the `DefaultEndpointResolver`
+    /// configured by `endpoint_type` and the endpoint overrides.
+    pub fn endpoint_resolver(mut self, resolver: impl EndpointResolver + 'static) -> Self {
+        self.endpoint_resolver = Some(Arc::new(resolver));
+        self
+    }
+
+    /// Sets the signature scheme used for every request.
+    pub fn signature_version(mut self, signature_version: SignatureVersion) -> Self {
+        self.signature_version = signature_version;
+        self
+    }
+
+    /// Sets the policy deciding whether failed requests are sent again
+    /// (`RetryPolicy::default()` by default).
+    pub fn retry_policy(mut self, retry_policy: RetryPolicy) -> Self {
+        self.retry_policy = retry_policy;
+        self
+    }
+
+    /// Sends every request through the given pre-configured HTTP client. The timeouts,
+    /// proxy, root certificates and user agent of the builder are then ignored.
+    pub fn http_client(mut self, http_client: reqwest::Client) -> Self {
+        self.http_client = Some(http_client);
+        self
+    }
+
+    /// Sends every request through the given transport instead of a `ReqwestTransport`,
+    /// e.g. a `MockTransport` to test offline. The HTTP settings of the builder are then
+    /// ignored.
+    pub fn transport(mut self, transport: impl HttpTransport + 'static) -> Self {
+        self.transport = Some(Arc::new(transport));
+        self
+    }
+
+    /// Creates the client, failing with `AdvancedClientError::Transport` when the HTTP
+    /// client cannot be initialized (e.g. the TLS backend rejects a root certificate).
+    pub fn build(self) -> Result<AliyunClient, AdvancedClientError> {
+        let http_client = match self.http_client {
+            Some(http_client) => http_client,
+            None => {
+                let mut builder = reqwest::Client::builder()
+                    .connect_timeout(self.connect_timeout)
+                    .read_timeout(self.read_timeout)
+                    .user_agent(self.user_agent);
+                if let Some(timeout) = self.timeout {
+                    builder = builder.timeout(timeout);
+                }
+                if let Some(proxy) = self.proxy {
+                    builder = builder.proxy(proxy);
+                }
+                for certificate in self.root_certificates {
+                    builder = builder.add_root_certificate(certificate);
+                }
+                builder.build()?
+            }
+        };
+        let transport = self
+            .transport
+            .unwrap_or_else(|| Arc::new(ReqwestTransport::new(http_client.clone())));
+        Ok(AliyunClient {
+            credentials_provider: self
+                .credentials_provider
+                .unwrap_or_else(|| Arc::new(DefaultCredentialsChain::new())),
+            http_client,
+            transport,
+            signature_version: self.signature_version,
+            default_region: self.default_region,
+            endpoint_resolver: self
+                .endpoint_resolver
+                .unwrap_or_else(|| Arc::new(self.default_endpoint_resolver)),
+            retry_policy: self.retry_policy,
+            clock: Clock::default(),
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::collections::BTreeMap;
+
+    use crate::test_utils::http_stub::StubServer;
+
+    #[test]
+    fn test_defaults() {
+        let client = AliyunClient::builder().build().unwrap();
+        assert_eq!(client.default_region(), None);
+        assert_eq!(client.signature_version, SignatureVersion::V1);
+        assert_eq!(client.endpoint("ecs", None), "ecs.aliyuncs.com");
+    }
+
+    #[test]
+    fn test_region_and_endpoint_overrides() {
+        let client = AliyunClient::builder()
+            .credentials("id".to_string(), "secret".to_string())
+            .default_region("cn-shanghai")
+            .endpoint_type(EndpointType::Vpc)
+            .region_endpoint_override("ecs", "cn-north-2-gov-1", "ecs.gov.example.com")
+            .signature_version(SignatureVersion::V3)
+            .build()
+            .unwrap();
+        assert_eq!(client.default_region(), Some("cn-shanghai"));
+        assert_eq!(client.signature_version, SignatureVersion::V3);
+        assert_eq!(
+            client.endpoint("ecs", None),
+            "ecs-vpc.cn-shanghai.aliyuncs.com"
+        );
+        assert_eq!(
+            client.endpoint("ecs", Some("cn-north-2-gov-1")),
+            "ecs.gov.example.com"
+        );
+        assert_eq!(client.endpoint("business", None), "business.aliyuncs.com");
+    }
+
+    #[tokio::test]
+    async fn test_requests_go_through_proxy() {
+        let proxy = StubServer::start(|_| (502, String::new())).await;
+        let client = AliyunClient::builder()
+            .credentials("id".to_string(), "secret".to_string())
+            .proxy(reqwest::Proxy::all(proxy.url()).unwrap())
+            .user_agent("my-app/1.0")
+            .retry_policy(RetryPolicy::disabled())
+            .build()
+            .unwrap();
+
+        let params = BTreeMap::from([("Action".to_string(), "DescribeRegions".to_string())]);
+        assert!(client
+            .send_request("ecs.aliyuncs.com", params)
+            .await
+            .is_err());
+
+        let requests = proxy.requests();
+        assert_eq!(requests.len(), 1);
+        assert_eq!(requests[0].method, "CONNECT");
+        assert_eq!(requests[0].path, "ecs.aliyuncs.com:443");
+    }
+
+    #[tokio::test]
+    async fn test_timeout() {
+        // Accepts connections but never answers.
+        let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let proxy_url = format!("http://{}", listener.local_addr().unwrap());
+        let client = AliyunClient::builder()
+            .credentials("id".to_string(), "secret".to_string())
+            .proxy(reqwest::Proxy::all(proxy_url).unwrap())
+            .timeout(Duration::from_millis(200))
+            .build()
+            .unwrap();
+
+        let params = BTreeMap::from([("Action".to_string(), "DescribeRegions".to_string())]);
+        let started = std::time::Instant::now();
+        assert!(matches!(
+            client.send_request("ecs.aliyuncs.com", params).await,
+            Err(AdvancedClientError::Timeout(_))
+        ));
+        assert!(started.elapsed() < Duration::from_secs(5));
+        drop(listener);
+    }
+}
@@ -0,0 +1,65 @@
+use std::sync::{Arc, Mutex};
+
+use chrono::{DateTime, TimeDelta, Utc};
+
+use crate::client::error::AdvancedClientError;
+
+/// The error code of a request whose timestamp is too far from the clock of Aliyun
+/// (more than 15 minutes).
+pub const CLOCK_SKEW_ERROR_CODE: &str = "InvalidTimeStamp.Expired";
+
+/// The offset between the local clock and the clock of Aliyun, measured from the `Date`
+/// header of a rejected request and shared by the clones of a client.
+#[derive(Debug, Clone, Default)]
+pub(crate) struct Clock {
+    skew: Arc<Mutex<Option<TimeDelta>>>,
+}
+
+impl Clock {
+    /// The current time on the clock of Aliyun, as far as it is known.
+    pub(crate) fn now(&self) -> DateTime<Utc> {
+        Utc::now() + self.skew().unwrap_or_default()
+    }
+
+    /// How far the clock of Aliyun is ahead of the local clock, or `None` until a skew was
+    /// measured.
+    pub(crate) fn skew(&self) -> Option<TimeDelta> {
+        *self.skew.lock().unwrap()
+    }
+
+    /// Measures the skew from the `Date` header of a response; unparsable dates are ignored.
+    pub(crate) fn correct(&self, date_header: &str) {
+        if let Ok(server_time) = DateTime::parse_from_rfc2822(date_header) {
+            *self.skew.lock().unwrap() = Some(server_time.with_timezone(&Utc) - Utc::now());
+        }
+    }
+}
+
+/// Whether Aliyun rejected the request because of its timestamp.
+pub(crate) fn is_clock_skew_error(error: &AdvancedClientError) -> bool {
+    matches!(error, AdvancedClientError::AliyunRejectError(rejection) if rejection.code == CLOCK_SKEW_ERROR_CODE)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_correct_from_date_header() {
+        let clock = Clock::default();
+        assert_eq!(clock.skew(), None);
+        clock.correct("yesterday");
+        assert_eq!(clock.skew(), None);
+
+        let server_time = Utc::now() + TimeDelta::hours(1);
+        clock.correct(&server_time.format("%a, %d %b %Y %H:%M:%S GMT").to_string());
+        let skew = clock.skew().unwrap();
+        assert!((skew - TimeDelta::hours(1)).abs() < TimeDelta::seconds(2));
+        assert!((clock.now() - server_time).abs() < TimeDelta::seconds(2));
+
+        // The clones of a client share the measured skew.
+        let clone = clock.clone();
+        clone.correct("Thu, 01 Jan 2015 00:00:00 GMT");
+        assert!(clock.skew().unwrap() < TimeDelta::days(-365));
+    }
+}
@@ -0,0 +1,180 @@
+use async_trait::async_trait;
+use std::time::Duration;
+
+use crate::client::{
+    credentials::{cache::CredentialsCache, Credentials, CredentialsError, CredentialsProvider},
+    AliyunClient,
+};
+
+/// Provides the temporary credentials of a RAM role obtained through STS `AssumeRole`.
+///
+/// The credentials are cached and transparently refreshed a configurable window
+/// (`DEFAULT_REFRESH_WINDOW` by default) before they expire, with a single in-flight
+/// `AssumeRole` call shared between concurrent requests. Wrap it in an `AliyunClient`
+/// to use a role in long-running processes without handling the session lifetime:
+///
+/// ```ignore
+/// let source = AliyunClient::new(access_key_id, access_key_secret);
+/// let provider = AssumeRoleCredentialsProvider::new(source, role_arn, "my-daemon")
+///     .with_duration(Duration::from_secs(3600));
+/// let client = AliyunClient::with_credentials_provider(provider);
+/// ```
+pub struct AssumeRoleCredentialsProvider {
+    client: AliyunClient,
+    role_arn: String,
+    role_session_name: String,
+    duration: Option<Duration>,
+    policy: Option<String>,
+    cache: CredentialsCache,
+}
+
+impl AssumeRoleCredentialsProvider {
+    /// Creates a provider assuming `role_arn` with the credentials of `client`.
+    pub fn new(
+        client: AliyunClient,
+        role_arn: impl Into<String>,
+        role_session_name: impl Into<String>,
+    ) -> Self {
+        Self {
+            client,
+            role_arn: role_arn.into(),
+            role_session_name: role_session_name.into(),
+            duration: None,
+            policy: None,
+            cache: CredentialsCache::default(),
+        }
+    }
+
+    /// Sets the validity period of each set of credentials (`DurationSeconds`).
+    pub fn with_duration(mut self, duration: Duration) -> Self {
+        self.duration = Some(duration);
+        self
+    }
+
+    /// Sets a policy further restricting the permissions of the credentials.
+    pub fn with_policy(mut self, policy: impl Into<String>) -> Self {
+        self.policy = Some(policy.into());
+        self
+    }
+
+    /// Sets how long before their expiration the credentials are refreshed.
+    pub fn with_refresh_window(mut self, refresh_window: Duration) -> Self {
+        self.cache.set_refresh_window(refresh_window);
+        self
+    }
+
+    async fn assume_role(&self) -> Result<Credentials, CredentialsError> {
+        let body = self
+            .client
+            .sts()
+            .assume_role(
+                &self.role_arn,
+                &self.role_session_name,
+                self.duration,
+                self.policy.as_deref(),
+            )
+            .await?;
+        Ok(body.credentials.into())
+    }
+}
+
+#[async_trait]
+impl CredentialsProvider for AssumeRoleCredentialsProvider {
+    async fn provide_credentials(&self) -> Result<Credentials, CredentialsError> {
+        self.cache.get_or_refresh(|| self.assume_role()).await
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::{DateTime, TimeDelta, Utc};
+    use claims::assert_matches;
+    use serde_json::json;
+
+    use crate::client::{
+        retry::RetryPolicy,
+        transport::{HttpResponse, MockTransport},
+    };
+
+    fn assume_role_response(access_key_id: &str, expiration: DateTime<Utc>) -> HttpResponse {
+        HttpResponse::json(&json!({
+            "RequestId": "6894B13B-6D71-4EF5-88FA-F32781734A7F",
+            "AssumedRoleUser": {
+                "AssumedRoleId": "344584339364951186:alibabacloud-rust",
+                "Arn": "acs:ram::123456789012****:role/testrole/alibabacloud-rust"
+            },
+            "Credentials": {
+                "SecurityToken": "token",
+                "AccessKeyId": access_key_id,
+                "AccessKeySecret": "secret",
+                "Expiration": expiration.format("%Y-%m-%dT%H:%M:%SZ").to_string()
+            }
+        }))
+    }
+
+    fn provider(transport: &MockTransport) -> AssumeRoleCredentialsProvider {
+        let source = AliyunClient::builder()
+            .credentials("id".to_string(), "secret".to_string())
+            .transport(transport.clone())
+            .retry_policy(RetryPolicy::disabled())
+            .build()
+            .unwrap();
+        AssumeRoleCredentialsProvider::new(
+            source,
+            "acs:ram::123456789012****:role/testrole",
+            "alibabacloud-rust",
+        )
+    }
+
+    #[tokio::test]
+    async fn test_credentials_are_cached_and_refreshed() {
+        let transport = MockTransport::new();
+        // Expires within the refresh window, then an hour later.
+        transport.push_response(assume_role_response(
+            "STS.near",
+            Utc::now() + TimeDelta::minutes(1),
+        ));
+        transport.push_response(assume_role_response(
+            "STS.far",
+            Utc::now() + TimeDelta::hours(1),
+        ));
+        let provider = provider(&transport);
+
+        let credentials = provider.provide_credentials().await.unwrap();
+        assert_eq!(credentials.access_key_id, "STS.near");
+        assert_eq!(credentials.security_token.as_deref(), Some("token"));
+
+        // The first credentials are about to expire, so they are fetched again.
+        let credentials = provider.provide_credentials().await.unwrap();
+        assert_eq!(credentials.access_key_id, "STS.far");
+        assert_eq!(transport.requests().len(), 2);
+
+        // The new ones are reused until their refresh window.
+        let credentials = provider.provide_credentials().await.unwrap();
+        assert_eq!(credentials.access_key_id, "STS.far");
+        assert_eq!(transport.requests().len(), 2);
+
+        let params = transport.requests()[0].query_params();
+        assert_eq!(params["Action"], "AssumeRole");
+        assert_eq!(params["RoleArn"], "acs:ram::123456789012****:role/testrole");
+        assert_eq!(params["RoleSessionName"], "alibabacloud-rust");
+    }
+
+    #[tokio::test]
+    async fn test_rejection_is_a_provider_error() {
+        let transport = MockTransport::new();
+        transport.push_response(HttpResponse::new(
+            403,
+            json!({
+                "RequestId": "7A3B4E8D-9C2F-4B1A-8E6D-5F0C1D2E3A4B",
+                "HostId": "sts.aliyuncs.com",
+                "Code": "NoPermission",
+                "Message": "You are not authorized to do this action."
+            })
+            .to_string(),
+        ));
+        let result = provider(&transport).provide_credentials().await;
+        assert_matches!(result, Err(CredentialsError::Provider(ref message)) if message.starts_with("NoPermission: "));
+    }
+}
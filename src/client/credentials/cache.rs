@@ -0,0 +1,175 @@
+use chrono::{TimeDelta, Utc};
+use std::{future::Future, time::Duration};
+use tokio::sync::Mutex;
+
+use crate::client::credentials::{Credentials, CredentialsError};
+
+/// The default time before `Expiration` at which cached credentials are refreshed.
+pub const DEFAULT_REFRESH_WINDOW: Duration = Duration::from_secs(180);
+
+/// A cache of temporary credentials, refreshed a configurable window before they expire.
+///
+/// Concurrent callers share a single in-flight refresh: the first caller to find the
+/// credentials stale refreshes them while holding the lock, the others wait for it and
+/// reuse the result.
+#[derive(Debug)]
+pub(crate) struct CredentialsCache {
+    cached: Mutex<Option<Credentials>>,
+    refresh_window: Duration,
+}
+
+impl CredentialsCache {
+    pub fn new(refresh_window: Duration) -> Self {
+        Self {
+            cached: Mutex::new(None),
+            refresh_window,
+        }
+    }
+
+    pub fn set_refresh_window(&mut self, refresh_window: Duration) {
+        self.refresh_window = refresh_window;
+    }
+
+    /// Returns the cached credentials, calling `refresh` first when they are missing or
+    /// about to expire.
+    ///
+    /// If the refresh fails while the cached credentials have not expired yet, those are
+    /// returned instead of the error.
+    pub async fn get_or_refresh<F, Fut>(&self, refresh: F) -> Result<Credentials, CredentialsError>
+    where
+        F: FnOnce() -> Fut,
+        Fut: Future<Output = Result<Credentials, CredentialsError>>,
+    {
+        let mut cached = self.cached.lock().await;
+        if let Some(credentials) = cached.as_ref() {
+            if !expires_within(credentials, self.refresh_window) {
+                return Ok(credentials.clone());
+            }
+        }
+
+        match refresh().await {
+            Ok(credentials) => {
+                *cached = Some(credentials.clone());
+                Ok(credentials)
+            }
+            Err(err) => match cached.as_ref() {
+                Some(credentials) if !expires_within(credentials, Duration::ZERO) => {
+                    Ok(credentials.clone())
+                }
+                _ => Err(err),
+            },
+        }
+    }
+}
+
+impl Default for CredentialsCache {
+    fn default() -> Self {
+        Self::new(DEFAULT_REFRESH_WINDOW)
+    }
+}
+
+/// Whether the credentials expire within `window` from now; credentials without an
+/// expiration never do.
+fn expires_within(credentials: &Credentials, window: Duration) -> bool {
+    let window = TimeDelta::from_std(window).unwrap_or(TimeDelta::MAX);
+    credentials
+        .expiration
+        .is_some_and(|expiration| expiration - window <= Utc::now())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::DateTime;
+    use claims::assert_matches;
+    use std::sync::atomic::{AtomicUsize, Ordering};
+
+    fn credentials_expiring_at(expiration: DateTime<Utc>) -> Credentials {
+        let mut credentials = Credentials::new("STS.id".to_string(), "secret".to_string())
+            .with_security_token("token".to_string());
+        credentials.expiration = Some(expiration);
+        credentials
+    }
+
+    #[tokio::test]
+    async fn test_reuses_fresh_credentials() {
+        let cache = CredentialsCache::default();
+        let refreshes = AtomicUsize::new(0);
+        let refresh = || async {
+            refreshes.fetch_add(1, Ordering::SeqCst);
+            Ok(credentials_expiring_at(Utc::now() + TimeDelta::hours(1)))
+        };
+
+        cache.get_or_refresh(refresh).await.unwrap();
+        cache.get_or_refresh(refresh).await.unwrap();
+        assert_eq!(refreshes.load(Ordering::SeqCst), 1);
+    }
+
+    #[tokio::test]
+    async fn test_refreshes_within_window() {
+        let cache = CredentialsCache::new(Duration::from_secs(600));
+        let refreshes = AtomicUsize::new(0);
+        let refresh = || async {
+            refreshes.fetch_add(1, Ordering::SeqCst);
+            Ok(credentials_expiring_at(Utc::now() + TimeDelta::minutes(5)))
+        };
+
+        cache.get_or_refresh(refresh).await.unwrap();
+        cache.get_or_refresh(refresh).await.unwrap();
+        assert_eq!(refreshes.load(Ordering::SeqCst), 2);
+    }
+
+    #[tokio::test]
+    async fn test_single_in_flight_refresh() {
+        let cache = CredentialsCache::default();
+        let refreshes = AtomicUsize::new(0);
+        let refresh = || async {
+            refreshes.fetch_add(1, Ordering::SeqCst);
+            // Let the other callers run while the refresh is in flight.
+            for _ in 0..10 {
+                tokio::task::yield_now().await;
+            }
+            Ok(credentials_expiring_at(Utc::now() + TimeDelta::hours(1)))
+        };
+
+        let (a, b, c) = tokio::join!(
+            cache.get_or_refresh(refresh),
+            cache.get_or_refresh(refresh),
+            cache.get_or_refresh(refresh)
+        );
+        assert_eq!(a.unwrap(), b.unwrap());
+        assert!(c.is_ok());
+        assert_eq!(refreshes.load(Ordering::SeqCst), 1);
+    }
+
+    #[tokio::test]
+    async fn test_failed_refresh_falls_back_to_unexpired_credentials() {
+        let cache = CredentialsCache::new(Duration::from_secs(600));
+        let expiring = credentials_expiring_at(Utc::now() + TimeDelta::minutes(5));
+        cache
+            .get_or_refresh(|| async { Ok(expiring.clone()) })
+            .await
+            .unwrap();
+
+        let result = cache
+            .get_or_refresh(|| async { Err(CredentialsError::Provider("down".to_string())) })
+            .await;
+        assert_eq!(result.unwrap(), expiring);
+    }
+
+    #[tokio::test]
+    async fn test_failed_refresh_of_expired_credentials() {
+        let cache = CredentialsCache::default();
+        cache
+            .get_or_refresh(|| async {
+                Ok(credentials_expiring_at(Utc::now() - TimeDelta::seconds(1)))
+            })
+            .await
+            .unwrap();
+
+        let result = cache
+            .get_or_refresh(|| async { Err(CredentialsError::Provider("down".to_string())) })
+            .await;
+        assert_matches!(result, Err(CredentialsError::Provider(_)));
+    }
+}
@@ -0,0 +1,305 @@
+use async_trait::async_trait;
+use std::{env, time::Duration};
+
+use crate::client::{
+    credentials::{cache::CredentialsCache, Credentials, CredentialsError, CredentialsProvider},
+    sts::credentials::StsCredentials,
+};
+
+pub const ENV_ECS_METADATA: &str = "ALIBABA_CLOUD_ECS_METADATA";
+pub const ENV_ECS_METADATA_DISABLED: &str = "ALIBABA_CLOUD_ECS_METADATA_DISABLED";
+pub const ENV_IMDSV1_DISABLED: &str = "ALIBABA_CLOUD_IMDSV1_DISABLED";
+
+/// The base URL of the ECS instance metadata service.
+pub const DEFAULT_METADATA_BASE_URL: &str = "http://100.100.100.200";
+
+const METADATA_TOKEN_PATH: &str = "/latest/api/token";
+const SECURITY_CREDENTIALS_PATH: &str = "/latest/meta-data/ram/security-credentials/";
+const METADATA_TOKEN_HEADER: &str = "X-aliyun-ecs-metadata-token";
+const METADATA_TOKEN_TTL_HEADER: &str = "X-aliyun-ecs-metadata-token-ttl-seconds";
+const METADATA_TOKEN_TTL_SECONDS: u32 = 21600;
+
+/// Loads the temporary credentials of the RAM role attached to the current ECS instance
+/// from the instance metadata service.
+///
+/// Every refresh first requests a metadata token (IMDSv2 hardening mode) and sends it
+/// with the metadata requests. When the token cannot be obtained the provider falls back
+/// to plain requests, unless `with_imds_v1_disabled(true)` is set or
+/// `ALIBABA_CLOUD_IMDSV1_DISABLED=true`. The credentials are cached and refreshed a
+/// configurable window before they expire.
+#[derive(Debug)]
+pub struct EcsRamRoleCredentialsProvider {
+    role_name: Option<String>,
+    enabled: bool,
+    metadata_base_url: String,
+    imds_v1_disabled: bool,
+    http_client: reqwest::Client,
+    cache: CredentialsCache,
+}
+
+impl EcsRamRoleCredentialsProvider {
+    /// Creates a provider for the given role, or for the role attached to the instance
+    /// when `role_name` is `None`.
+    pub fn new(role_name: Option<String>) -> Self {
+        let http_client = reqwest::Client::builder()
+            .connect_timeout(Duration::from_secs(1))
+            .timeout(Duration::from_secs(5))
+            .build()
+            .unwrap_or_default();
+        Self {
+            role_name: role_name.filter(|name| !name.is_empty()),
+            enabled: true,
+            metadata_base_url: DEFAULT_METADATA_BASE_URL.to_string(),
+            imds_v1_disabled: env::var(ENV_IMDSV1_DISABLED)
+                .is_ok_and(|value| value.eq_ignore_ascii_case("true")),
+            http_client,
+            cache: CredentialsCache::default(),
+        }
+    }
+
+    /// Creates a provider for the role named by `ALIBABA_CLOUD_ECS_METADATA`; the provider
+    /// is not configured when the variable is unset or `ALIBABA_CLOUD_ECS_METADATA_DISABLED=true`.
+    pub fn from_env() -> Self {
+        let disabled = env::var(ENV_ECS_METADATA_DISABLED)
+            .is_ok_and(|value| value.eq_ignore_ascii_case("true"));
+        match env::var(ENV_ECS_METADATA) {
+            Ok(role_name) if !role_name.is_empty() && !disabled => Self::new(Some(role_name)),
+            _ => Self::default(),
+        }
+    }
+
+    /// Uses another metadata service, e.g. a local stub in tests.
+    pub fn with_metadata_base_url(mut self, metadata_base_url: impl Into<String>) -> Self {
+        self.metadata_base_url = metadata_base_url.into().trim_end_matches('/').to_string();
+        self
+    }
+
+    /// Fails instead of falling back to requests without a metadata token.
+    pub fn with_imds_v1_disabled(mut self, imds_v1_disabled: bool) -> Self {
+        self.imds_v1_disabled = imds_v1_disabled;
+        self
+    }
+
+    /// Sets how long before their expiration the credentials are refreshed.
+    pub fn with_refresh_window(mut self, refresh_window: Duration) -> Self {
+        self.cache.set_refresh_window(refresh_window);
+        self
+    }
+
+    async fn metadata_token(&self) -> Result<Option<String>, CredentialsError> {
+        let result = self
+            .http_client
+            .put(format!("{}{}", self.metadata_base_url, METADATA_TOKEN_PATH))
+            .header(METADATA_TOKEN_TTL_HEADER, METADATA_TOKEN_TTL_SECONDS)
+            .send()
+            .await
+            .and_then(|response| response.error_for_status());
+        match result {
+            Ok(response) => Ok(Some(response.text().await?)),
+            Err(_) if !self.imds_v1_disabled => Ok(None),
+            Err(e) => Err(CredentialsError::Provider(format!(
+                "failed to get the metadata token and IMDSv1 is disabled: {}",
+                e
+            ))),
+        }
+    }
+
+    async fn get_metadata(
+        &self,
+        path: &str,
+        token: Option<&str>,
+    ) -> Result<String, CredentialsError> {
+        let mut request = self
+            .http_client
+            .get(format!("{}{}", self.metadata_base_url, path));
+        if let Some(token) = token {
+            request = request.header(METADATA_TOKEN_HEADER, token);
+        }
+        Ok(request.send().await?.error_for_status()?.text().await?)
+    }
+
+    async fn fetch_credentials(&self) -> Result<Credentials, CredentialsError> {
+        let token = self.metadata_token().await?;
+        let role_name = match &self.role_name {
+            Some(role_name) => role_name.clone(),
+            None => self
+                .get_metadata(SECURITY_CREDENTIALS_PATH, token.as_deref())
+                .await?
+                .trim()
+                .to_string(),
+        };
+        let body = self
+            .get_metadata(
+                &format!("{}{}", SECURITY_CREDENTIALS_PATH, role_name),
+                token.as_deref(),
+            )
+            .await?;
+        let body: serde_json::Value = serde_json::from_str(&body)?;
+        if body["Code"] != "Success" {
+            return Err(CredentialsError::Provider(format!(
+                "failed to get the credentials of RAM role '{}': {}",
+                role_name, body
+            )));
+        }
+        Ok(serde_json::from_value::<StsCredentials>(body)?.into())
+    }
+}
+
+impl Default for EcsRamRoleCredentialsProvider {
+    /// A disabled provider, which is never configured.
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            ..Self::new(None)
+        }
+    }
+}
+
+#[async_trait]
+impl CredentialsProvider for EcsRamRoleCredentialsProvider {
+    async fn provide_credentials(&self) -> Result<Credentials, CredentialsError> {
+        if !self.enabled {
+            return Err(CredentialsError::NotConfigured(format!(
+                "{} is not set",
+                ENV_ECS_METADATA
+            )));
+        }
+        self.cache.get_or_refresh(|| self.fetch_credentials()).await
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::{TimeDelta, Utc};
+    use claims::assert_matches;
+
+    use crate::test_utils::http_stub::{StubRequest, StubResponse, StubServer};
+
+    const ROLE_NAME: &str = "EcsRamRoleTest";
+
+    fn credentials_body(code: &str, expires_in: TimeDelta) -> String {
+        serde_json::json!({
+            "AccessKeyId": "STS.id",
+            "AccessKeySecret": "secret",
+            "Expiration": (Utc::now() + expires_in).format("%Y-%m-%dT%H:%M:%SZ").to_string(),
+            "SecurityToken": "token",
+            "LastUpdated": Utc::now().format("%Y-%m-%dT%H:%M:%SZ").to_string(),
+            "Code": code
+        })
+        .to_string()
+    }
+
+    /// A metadata service requiring the IMDSv2 token on every request.
+    fn imds_v2(request: &StubRequest) -> StubResponse {
+        if request.method == "PUT" && request.path == METADATA_TOKEN_PATH {
+            return (200, "metadata-token".to_string());
+        }
+        if request
+            .headers
+            .get("x-aliyun-ecs-metadata-token")
+            .map(String::as_str)
+            != Some("metadata-token")
+        {
+            return (401, String::new());
+        }
+        match request.path.strip_prefix(SECURITY_CREDENTIALS_PATH) {
+            Some("") => (200, ROLE_NAME.to_string()),
+            Some(ROLE_NAME) => (200, credentials_body("Success", TimeDelta::hours(6))),
+            _ => (404, String::new()),
+        }
+    }
+
+    /// A metadata service without IMDSv2 support.
+    fn imds_v1(request: &StubRequest) -> StubResponse {
+        match request.path.strip_prefix(SECURITY_CREDENTIALS_PATH) {
+            Some(ROLE_NAME) => (200, credentials_body("Success", TimeDelta::hours(6))),
+            _ => (404, String::new()),
+        }
+    }
+
+    #[tokio::test]
+    async fn test_imds_v2_discovers_role_and_caches() {
+        let server = StubServer::start(imds_v2).await;
+        let provider = EcsRamRoleCredentialsProvider::new(None)
+            .with_metadata_base_url(server.url())
+            .with_imds_v1_disabled(true);
+
+        let credentials = provider.provide_credentials().await.unwrap();
+        assert_eq!(credentials.access_key_id, "STS.id");
+        assert_eq!(credentials.security_token.as_deref(), Some("token"));
+
+        let requests = server.requests();
+        assert_eq!(requests.len(), 3);
+        assert_eq!(
+            requests[0]
+                .headers
+                .get("x-aliyun-ecs-metadata-token-ttl-seconds"),
+            Some(&"21600".to_string())
+        );
+        assert_eq!(
+            requests[2].path,
+            format!("{}{}", SECURITY_CREDENTIALS_PATH, ROLE_NAME)
+        );
+
+        provider.provide_credentials().await.unwrap();
+        assert_eq!(server.requests().len(), 3);
+    }
+
+    #[tokio::test]
+    async fn test_imds_v1_fallback() {
+        let server = StubServer::start(imds_v1).await;
+        let provider = EcsRamRoleCredentialsProvider::new(Some(ROLE_NAME.to_string()))
+            .with_metadata_base_url(server.url())
+            .with_imds_v1_disabled(false);
+        assert!(provider.provide_credentials().await.is_ok());
+
+        let provider = EcsRamRoleCredentialsProvider::new(Some(ROLE_NAME.to_string()))
+            .with_metadata_base_url(server.url())
+            .with_imds_v1_disabled(true);
+        assert_matches!(
+            provider.provide_credentials().await,
+            Err(CredentialsError::Provider(_))
+        );
+    }
+
+    #[tokio::test]
+    async fn test_refreshes_before_expiration() {
+        let server = StubServer::start(|request: &StubRequest| match request.path.as_str() {
+            METADATA_TOKEN_PATH => (200, "metadata-token".to_string()),
+            _ => (200, credentials_body("Success", TimeDelta::minutes(1))),
+        })
+        .await;
+        let provider = EcsRamRoleCredentialsProvider::new(Some(ROLE_NAME.to_string()))
+            .with_metadata_base_url(server.url());
+
+        provider.provide_credentials().await.unwrap();
+        provider.provide_credentials().await.unwrap();
+        assert_eq!(server.requests().len(), 4);
+    }
+
+    #[tokio::test]
+    async fn test_failure_code() {
+        let server = StubServer::start(|request: &StubRequest| match request.path.as_str() {
+            METADATA_TOKEN_PATH => (200, "metadata-token".to_string()),
+            _ => (200, credentials_body("Failed", TimeDelta::hours(6))),
+        })
+        .await;
+        let provider = EcsRamRoleCredentialsProvider::new(Some(ROLE_NAME.to_string()))
+            .with_metadata_base_url(server.url());
+        assert_matches!(
+            provider.provide_credentials().await,
+            Err(CredentialsError::Provider(_))
+        );
+    }
+
+    #[tokio::test]
+    async fn test_disabled_provider_is_not_configured() {
+        assert_matches!(
+            EcsRamRoleCredentialsProvider::default()
+                .provide_credentials()
+                .await,
+            Err(CredentialsError::NotConfigured(_))
+        );
+    }
+}
@@ -0,0 +1,85 @@
+use async_trait::async_trait;
+use std::env;
+
+use crate::client::credentials::{Credentials, CredentialsError, CredentialsProvider};
+
+pub const ENV_ACCESS_KEY_ID: &str = "ALIBABA_CLOUD_ACCESS_KEY_ID";
+pub const ENV_ACCESS_KEY_SECRET: &str = "ALIBABA_CLOUD_ACCESS_KEY_SECRET";
+pub const ENV_SECURITY_TOKEN: &str = "ALIBABA_CLOUD_SECURITY_TOKEN";
+
+/// Loads credentials from the `ALIBABA_CLOUD_ACCESS_KEY_ID`, `ALIBABA_CLOUD_ACCESS_KEY_SECRET`
+/// and (optionally) `ALIBABA_CLOUD_SECURITY_TOKEN` environment variables.
+#[derive(Debug, Default, Clone)]
+pub struct EnvironmentCredentialsProvider;
+
+impl EnvironmentCredentialsProvider {
+    pub fn new() -> Self {
+        Self
+    }
+
+    fn load(var: impl Fn(&str) -> Option<String>) -> Result<Credentials, CredentialsError> {
+        let non_empty = |name: &str| var(name).filter(|value| !value.is_empty());
+        let access_key_id = non_empty(ENV_ACCESS_KEY_ID).ok_or_else(|| {
+            CredentialsError::NotConfigured(format!("{} is not set", ENV_ACCESS_KEY_ID))
+        })?;
+        let access_key_secret = non_empty(ENV_ACCESS_KEY_SECRET).ok_or_else(|| {
+            CredentialsError::NotConfigured(format!("{} is not set", ENV_ACCESS_KEY_SECRET))
+        })?;
+
+        let mut credentials = Credentials::new(access_key_id, access_key_secret);
+        credentials.security_token = non_empty(ENV_SECURITY_TOKEN);
+        Ok(credentials)
+    }
+}
+
+#[async_trait]
+impl CredentialsProvider for EnvironmentCredentialsProvider {
+    async fn provide_credentials(&self) -> Result<Credentials, CredentialsError> {
+        Self::load(|name| env::var(name).ok())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use claims::assert_matches;
+    use std::collections::HashMap;
+
+    fn load_from(vars: &[(&str, &str)]) -> Result<Credentials, CredentialsError> {
+        let vars: HashMap<String, String> = vars
+            .iter()
+            .map(|(k, v)| (k.to_string(), v.to_string()))
+            .collect();
+        EnvironmentCredentialsProvider::load(|name| vars.get(name).cloned())
+    }
+
+    #[test]
+    fn test_load_access_key() {
+        let credentials =
+            load_from(&[(ENV_ACCESS_KEY_ID, "id"), (ENV_ACCESS_KEY_SECRET, "secret")]).unwrap();
+        assert_eq!(
+            credentials,
+            Credentials::new("id".to_string(), "secret".to_string())
+        );
+    }
+
+    #[test]
+    fn test_load_security_token() {
+        let credentials = load_from(&[
+            (ENV_ACCESS_KEY_ID, "id"),
+            (ENV_ACCESS_KEY_SECRET, "secret"),
+            (ENV_SECURITY_TOKEN, "token"),
+        ])
+        .unwrap();
+        assert_eq!(credentials.security_token.as_deref(), Some("token"));
+    }
+
+    #[test]
+    fn test_missing_or_empty_variables_are_not_configured() {
+        assert_matches!(load_from(&[]), Err(CredentialsError::NotConfigured(_)));
+        assert_matches!(
+            load_from(&[(ENV_ACCESS_KEY_ID, "id"), (ENV_ACCESS_KEY_SECRET, "")]),
+            Err(CredentialsError::NotConfigured(_))
+        );
+    }
+}
@@ -0,0 +1,40 @@
+use std::path::PathBuf;
+use thiserror::Error;
+
+use crate::client::error::AdvancedClientError;
+
+/// Error type for credentials loading failures
+#[derive(Debug, Error)]
+pub enum CredentialsError {
+    /// The provider's source is not configured (e.g., the environment variables are unset).
+    /// A `DefaultCredentialsChain` moves on to the next provider on this error.
+    #[error("Credentials provider is not configured: {0}")]
+    NotConfigured(String),
+    #[error("No credentials provider in the chain could provide credentials: [{}]", .0.join("; "))]
+    ChainExhausted(Vec<String>),
+    #[error("Failed to read the credentials file '{path}': {source}")]
+    Io {
+        path: PathBuf,
+        #[source]
+        source: std::io::Error,
+    },
+    #[error("The credentials profile is invalid: {0}")]
+    InvalidProfile(String),
+    #[error("Failed to deserialize the credentials: {0}")]
+    Deserialization(#[from] serde_json::Error),
+    #[error("Failed to request credentials: {0}")]
+    Http(#[from] reqwest::Error),
+    #[error("The credentials source returned an error: {0}")]
+    Provider(String),
+}
+
+impl From<AdvancedClientError> for CredentialsError {
+    fn from(err: AdvancedClientError) -> Self {
+        match err {
+            AdvancedClientError::AliyunRejectError(rejection) => {
+                CredentialsError::Provider(format!("{}: {}", rejection.code, rejection.message))
+            }
+            err => CredentialsError::Provider(err.to_string()),
+        }
+    }
+}
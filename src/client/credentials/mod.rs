@@ -0,0 +1,212 @@
+pub mod assume_role;
+mod cache;
+pub mod ecs_ram_role;
+pub mod environment;
+pub mod error;
+pub mod oidc;
+pub mod profile;
+
+pub use assume_role::AssumeRoleCredentialsProvider;
+pub use cache::DEFAULT_REFRESH_WINDOW;
+pub use ecs_ram_role::EcsRamRoleCredentialsProvider;
+pub use environment::EnvironmentCredentialsProvider;
+pub use error::CredentialsError;
+pub use oidc::OidcRoleArnCredentialsProvider;
+pub use profile::ProfileCredentialsProvider;
+
+use async_trait::async_trait;
+use chrono::{DateTime, Utc};
+use std::fmt;
+
+/// A set of credentials used to sign requests.
+///
+/// Temporary credentials issued by STS carry a `security_token` and an `expiration`;
+/// long-lived AccessKey pairs carry neither.
+#[derive(Clone, PartialEq, Eq)]
+pub struct Credentials {
+    pub access_key_id: String,
+    pub access_key_secret: String,
+    pub security_token: Option<String>,
+    pub expiration: Option<DateTime<Utc>>,
+}
+
+impl Credentials {
+    /// Creates long-lived credentials from an AccessKey pair.
+    pub fn new(access_key_id: String, access_key_secret: String) -> Self {
+        Self {
+            access_key_id,
+            access_key_secret,
+            security_token: None,
+            expiration: None,
+        }
+    }
+
+    /// Attaches the security token of temporary STS credentials.
+    pub fn with_security_token(mut self, security_token: String) -> Self {
+        self.security_token = Some(security_token);
+        self
+    }
+}
+
+impl fmt::Debug for Credentials {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("Credentials")
+            .field("access_key_id", &self.access_key_id)
+            .field("access_key_secret", &"** redacted **")
+            .field(
+                "security_token",
+                &self.security_token.as_ref().map(|_| "** redacted **"),
+            )
+            .field("expiration", &self.expiration)
+            .finish()
+    }
+}
+
+/// A source of credentials, asked for credentials before every request.
+///
+/// Implementations backed by temporary credentials are expected to return credentials
+/// that are still valid, refreshing them when needed.
+#[async_trait]
+pub trait CredentialsProvider: Send + Sync {
+    async fn provide_credentials(&self) -> Result<Credentials, CredentialsError>;
+}
+
+/// A provider that always returns the same credentials.
+#[derive(Debug, Clone)]
+pub struct StaticCredentialsProvider {
+    credentials: Credentials,
+}
+
+impl StaticCredentialsProvider {
+    pub fn new(credentials: Credentials) -> Self {
+        Self { credentials }
+    }
+}
+
+#[async_trait]
+impl CredentialsProvider for StaticCredentialsProvider {
+    async fn provide_credentials(&self) -> Result<Credentials, CredentialsError> {
+        Ok(self.credentials.clone())
+    }
+}
+
+/// A chain of providers tried in order until one of them provides credentials.
+///
+/// Providers answering `CredentialsError::NotConfigured` are skipped; any other error
+/// stops the chain and is returned to the caller.
+pub struct DefaultCredentialsChain {
+    providers: Vec<Box<dyn CredentialsProvider>>,
+}
+
+impl DefaultCredentialsChain {
+    /// Creates the default chain, which looks for credentials in the following order:
+    ///
+    /// 1. Environment variables (`ALIBABA_CLOUD_ACCESS_KEY_ID`, ...)
+    /// 2. OIDC role ARN (`ALIBABA_CLOUD_ROLE_ARN`, `ALIBABA_CLOUD_OIDC_PROVIDER_ARN`, ...)
+    /// 3. The `~/.aliyun/config.json` profile file
+    /// 4. The ECS instance RAM role (`ALIBABA_CLOUD_ECS_METADATA`)
+    pub fn new() -> Self {
+        Self::from_providers(vec![
+            Box::new(EnvironmentCredentialsProvider::new()),
+            Box::new(OidcRoleArnCredentialsProvider::from_env()),
+            Box::new(ProfileCredentialsProvider::new()),
+            Box::new(EcsRamRoleCredentialsProvider::from_env()),
+        ])
+    }
+
+    /// Creates a chain from a custom list of providers.
+    pub fn from_providers(providers: Vec<Box<dyn CredentialsProvider>>) -> Self {
+        Self { providers }
+    }
+}
+
+impl Default for DefaultCredentialsChain {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+#[async_trait]
+impl CredentialsProvider for DefaultCredentialsChain {
+    async fn provide_credentials(&self) -> Result<Credentials, CredentialsError> {
+        let mut skipped = Vec::new();
+        for provider in &self.providers {
+            match provider.provide_credentials().await {
+                Err(CredentialsError::NotConfigured(reason)) => skipped.push(reason),
+                result => return result,
+            }
+        }
+        Err(CredentialsError::ChainExhausted(skipped))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use claims::assert_matches;
+
+    struct Unconfigured;
+
+    #[async_trait]
+    impl CredentialsProvider for Unconfigured {
+        async fn provide_credentials(&self) -> Result<Credentials, CredentialsError> {
+            Err(CredentialsError::NotConfigured("unconfigured".to_string()))
+        }
+    }
+
+    struct Broken;
+
+    #[async_trait]
+    impl CredentialsProvider for Broken {
+        async fn provide_credentials(&self) -> Result<Credentials, CredentialsError> {
+            Err(CredentialsError::Provider("broken".to_string()))
+        }
+    }
+
+    fn static_provider(id: &str) -> Box<dyn CredentialsProvider> {
+        Box::new(StaticCredentialsProvider::new(Credentials::new(
+            id.to_string(),
+            "secret".to_string(),
+        )))
+    }
+
+    #[tokio::test]
+    async fn test_chain_skips_unconfigured_providers() {
+        let chain = DefaultCredentialsChain::from_providers(vec![
+            Box::new(Unconfigured),
+            static_provider("first"),
+            static_provider("second"),
+        ]);
+        let credentials = chain.provide_credentials().await.unwrap();
+        assert_eq!(credentials.access_key_id, "first");
+    }
+
+    #[tokio::test]
+    async fn test_chain_stops_on_error() {
+        let chain =
+            DefaultCredentialsChain::from_providers(vec![Box::new(Broken), static_provider("id")]);
+        assert_matches!(
+            chain.provide_credentials().await,
+            Err(CredentialsError::Provider(_))
+        );
+    }
+
+    #[tokio::test]
+    async fn test_chain_exhausted() {
+        let chain = DefaultCredentialsChain::from_providers(vec![
+            Box::new(Unconfigured),
+            Box::new(Unconfigured),
+        ]);
+        let result = chain.provide_credentials().await;
+        assert_matches!(result, Err(CredentialsError::ChainExhausted(ref reasons)) if reasons.len() == 2);
+    }
+
+    #[test]
+    fn test_debug_redacts_secrets() {
+        let mut credentials = Credentials::new("id".to_string(), "secret".to_string());
+        credentials.security_token = Some("token".to_string());
+        let debug = format!("{:?}", credentials);
+        assert!(!debug.contains("secret\""));
+        assert!(!debug.contains("token\""));
+    }
+}
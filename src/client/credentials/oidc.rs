@@ -0,0 +1,152 @@
+use async_trait::async_trait;
+use chrono::Utc;
+use std::{env, path::PathBuf};
+
+use crate::client::{
+    credentials::{Credentials, CredentialsError, CredentialsProvider},
+    AliyunClient,
+};
+
+pub const ENV_ROLE_ARN: &str = "ALIBABA_CLOUD_ROLE_ARN";
+pub const ENV_OIDC_PROVIDER_ARN: &str = "ALIBABA_CLOUD_OIDC_PROVIDER_ARN";
+pub const ENV_OIDC_TOKEN_FILE: &str = "ALIBABA_CLOUD_OIDC_TOKEN_FILE";
+pub const ENV_ROLE_SESSION_NAME: &str = "ALIBABA_CLOUD_ROLE_SESSION_NAME";
+
+/// Exchanges an OIDC token for the temporary credentials of a RAM role through the STS
+/// `AssumeRoleWithOIDC` operation, as used by RRSA on Container Service for Kubernetes.
+///
+/// The token file is read again on every call, since it is rotated by the platform.
+#[derive(Debug, Clone)]
+pub struct OidcRoleArnCredentialsProvider {
+    config: Option<OidcRoleArnConfig>,
+    client: AliyunClient,
+}
+
+#[derive(Debug, Clone)]
+struct OidcRoleArnConfig {
+    role_arn: String,
+    oidc_provider_arn: String,
+    oidc_token_file: PathBuf,
+    role_session_name: Option<String>,
+}
+
+impl OidcRoleArnCredentialsProvider {
+    pub fn new(
+        role_arn: String,
+        oidc_provider_arn: String,
+        oidc_token_file: impl Into<PathBuf>,
+    ) -> Self {
+        Self {
+            config: Some(OidcRoleArnConfig {
+                role_arn,
+                oidc_provider_arn,
+                oidc_token_file: oidc_token_file.into(),
+                role_session_name: None,
+            }),
+            client: AliyunClient::anonymous(),
+        }
+    }
+
+    /// Creates a provider from the `ALIBABA_CLOUD_ROLE_ARN`, `ALIBABA_CLOUD_OIDC_PROVIDER_ARN`,
+    /// `ALIBABA_CLOUD_OIDC_TOKEN_FILE` and (optionally) `ALIBABA_CLOUD_ROLE_SESSION_NAME`
+    /// environment variables; the provider is not configured when any of the first three is unset.
+    pub fn from_env() -> Self {
+        let var = |name: &str| env::var(name).ok().filter(|value| !value.is_empty());
+        let config = match (
+            var(ENV_ROLE_ARN),
+            var(ENV_OIDC_PROVIDER_ARN),
+            var(ENV_OIDC_TOKEN_FILE),
+        ) {
+            (Some(role_arn), Some(oidc_provider_arn), Some(oidc_token_file)) => {
+                Some(OidcRoleArnConfig {
+                    role_arn,
+                    oidc_provider_arn,
+                    oidc_token_file: oidc_token_file.into(),
+                    role_session_name: var(ENV_ROLE_SESSION_NAME),
+                })
+            }
+            _ => None,
+        };
+        Self {
+            config,
+            client: AliyunClient::anonymous(),
+        }
+    }
+
+    /// Sets the session name of the assumed role.
+    pub fn with_role_session_name(mut self, role_session_name: impl Into<String>) -> Self {
+        if let Some(config) = &mut self.config {
+            config.role_session_name = Some(role_session_name.into());
+        }
+        self
+    }
+}
+
+#[async_trait]
+impl CredentialsProvider for OidcRoleArnCredentialsProvider {
+    async fn provide_credentials(&self) -> Result<Credentials, CredentialsError> {
+        let config = self.config.as_ref().ok_or_else(|| {
+            CredentialsError::NotConfigured(format!(
+                "{}, {} or {} is not set",
+                ENV_ROLE_ARN, ENV_OIDC_PROVIDER_ARN, ENV_OIDC_TOKEN_FILE
+            ))
+        })?;
+        let oidc_token = std::fs::read_to_string(&config.oidc_token_file).map_err(|source| {
+            CredentialsError::Io {
+                path: config.oidc_token_file.clone(),
+                source,
+            }
+        })?;
+
+        let role_session_name = config
+            .role_session_name
+            .clone()
+            .unwrap_or_else(|| format!("alibabacloud-rust-{}", Utc::now().timestamp_millis()));
+
+        // `AssumeRoleWithOIDC` is an anonymous operation: no credentials are needed to call it.
+        let body = self
+            .client
+            .sts()
+            .assume_role_with_oidc_token(
+                &config.oidc_provider_arn,
+                &config.role_arn,
+                oidc_token.trim(),
+                Some(&role_session_name),
+                None,
+                None,
+            )
+            .await?;
+        Ok(body.credentials.into())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use claims::assert_matches;
+
+    #[tokio::test]
+    async fn test_unset_environment_is_not_configured() {
+        let provider = OidcRoleArnCredentialsProvider {
+            config: None,
+            client: AliyunClient::anonymous(),
+        };
+        assert_matches!(
+            provider.provide_credentials().await,
+            Err(CredentialsError::NotConfigured(_))
+        );
+    }
+
+    #[tokio::test]
+    async fn test_missing_token_file() {
+        let provider = OidcRoleArnCredentialsProvider::new(
+            "acs:ram::123456789012****:role/testrole".to_string(),
+            "acs:ram::123456789012****:oidc-provider/TestOidcIdp".to_string(),
+            "/nonexistent/oidc/token",
+        );
+        assert_matches!(
+            provider.provide_credentials().await,
+            Err(CredentialsError::Io { .. })
+        );
+    }
+}
@@ -0,0 +1,294 @@
+use async_trait::async_trait;
+use serde::Deserialize;
+use std::{
+    env,
+    path::PathBuf,
+    sync::{Arc, Mutex},
+};
+
+use crate::client::credentials::{
+    Credentials, CredentialsError, CredentialsProvider, EcsRamRoleCredentialsProvider,
+};
+
+pub const ENV_PROFILE: &str = "ALIBABA_CLOUD_PROFILE";
+
+/// The `~/.aliyun/config.json` file written by the aliyun CLI.
+#[derive(Deserialize, Debug)]
+struct ConfigFile {
+    #[serde(default)]
+    current: String,
+    #[serde(default)]
+    profiles: Vec<Profile>,
+}
+
+#[derive(Deserialize, Debug)]
+struct Profile {
+    name: String,
+    #[serde(default)]
+    mode: String,
+    #[serde(default)]
+    access_key_id: String,
+    #[serde(default)]
+    access_key_secret: String,
+    #[serde(default)]
+    sts_token: String,
+    #[serde(default)]
+    ram_role_name: String,
+}
+
+/// Loads credentials from a profile of the aliyun CLI configuration file.
+///
+/// The profile is the one named by `ALIBABA_CLOUD_PROFILE`, or the file's `current` profile.
+/// The `AK`, `StsToken` and `EcsRamRole` modes are supported. In `EcsRamRole` mode the
+/// `EcsRamRoleCredentialsProvider` of the role is built once and kept, with its metadata
+/// token and credentials cache, until the profile names another role.
+#[derive(Debug, Default, Clone)]
+pub struct ProfileCredentialsProvider {
+    path: Option<PathBuf>,
+    profile_name: Option<String>,
+    metadata_base_url: Option<String>,
+    ecs_ram_role: Arc<Mutex<Option<RoleProvider>>>,
+}
+
+/// The `EcsRamRole` provider built for a role name.
+type RoleProvider = (String, Arc<EcsRamRoleCredentialsProvider>);
+
+impl ProfileCredentialsProvider {
+    /// Creates a provider reading `~/.aliyun/config.json`.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Reads the configuration from the given file instead of `~/.aliyun/config.json`.
+    pub fn with_path(mut self, path: impl Into<PathBuf>) -> Self {
+        self.path = Some(path.into());
+        self
+    }
+
+    /// Uses the given profile instead of `ALIBABA_CLOUD_PROFILE` or the `current` profile.
+    pub fn with_profile_name(mut self, profile_name: impl Into<String>) -> Self {
+        self.profile_name = Some(profile_name.into());
+        self
+    }
+
+    /// Uses another metadata service in `EcsRamRole` mode, e.g. a local stub in tests.
+    pub fn with_metadata_base_url(mut self, metadata_base_url: impl Into<String>) -> Self {
+        self.metadata_base_url = Some(metadata_base_url.into());
+        self
+    }
+
+    /// The provider of the role, reused as long as the profile names the same role.
+    fn ecs_ram_role_provider(&self, role_name: String) -> Arc<EcsRamRoleCredentialsProvider> {
+        let mut cached = self.ecs_ram_role.lock().unwrap();
+        match cached.as_ref() {
+            Some((name, provider)) if *name == role_name => provider.clone(),
+            _ => {
+                let mut provider = EcsRamRoleCredentialsProvider::new(Some(role_name.clone()));
+                if let Some(url) = &self.metadata_base_url {
+                    provider = provider.with_metadata_base_url(url);
+                }
+                let provider = Arc::new(provider);
+                *cached = Some((role_name, provider.clone()));
+                provider
+            }
+        }
+    }
+
+    fn config_path(&self) -> Option<PathBuf> {
+        self.path.clone().or_else(|| {
+            env::var_os("HOME")
+                .or_else(|| env::var_os("USERPROFILE"))
+                .map(|home| PathBuf::from(home).join(".aliyun").join("config.json"))
+        })
+    }
+
+    fn select_profile(&self, config: ConfigFile) -> Result<Profile, CredentialsError> {
+        let name = self
+            .profile_name
+            .clone()
+            .or_else(|| env::var(ENV_PROFILE).ok().filter(|name| !name.is_empty()))
+            .unwrap_or(config.current);
+        config
+            .profiles
+            .into_iter()
+            .find(|profile| profile.name == name)
+            .ok_or_else(|| {
+                CredentialsError::InvalidProfile(format!("profile '{}' not found", name))
+            })
+    }
+}
+
+#[async_trait]
+impl CredentialsProvider for ProfileCredentialsProvider {
+    async fn provide_credentials(&self) -> Result<Credentials, CredentialsError> {
+        let path = self.config_path().ok_or_else(|| {
+            CredentialsError::NotConfigured("cannot locate the home directory".to_string())
+        })?;
+        if !path.exists() {
+            return Err(CredentialsError::NotConfigured(format!(
+                "{} does not exist",
+                path.display()
+            )));
+        }
+        let content = std::fs::read_to_string(&path).map_err(|source| CredentialsError::Io {
+            path: path.clone(),
+            source,
+        })?;
+        let profile = self.select_profile(serde_json::from_str(&content)?)?;
+
+        match profile.mode.as_str() {
+            "AK" => Ok(Credentials::new(
+                profile.access_key_id,
+                profile.access_key_secret,
+            )),
+            "StsToken" => Ok(
+                Credentials::new(profile.access_key_id, profile.access_key_secret)
+                    .with_security_token(profile.sts_token),
+            ),
+            "EcsRamRole" => {
+                self.ecs_ram_role_provider(profile.ram_role_name)
+                    .provide_credentials()
+                    .await
+            }
+            mode => Err(CredentialsError::InvalidProfile(format!(
+                "unsupported mode '{}' in profile '{}'",
+                mode, profile.name
+            ))),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::{TimeDelta, Utc};
+    use claims::assert_matches;
+    use uuid::Uuid;
+
+    use crate::test_utils::http_stub::{StubRequest, StubServer};
+
+    const CONFIG: &str = r#"{
+        "current": "default",
+        "profiles": [
+            {
+                "name": "default",
+                "mode": "AK",
+                "access_key_id": "default-id",
+                "access_key_secret": "default-secret",
+                "region_id": "cn-hangzhou"
+            },
+            {
+                "name": "sts",
+                "mode": "StsToken",
+                "access_key_id": "STS.id",
+                "access_key_secret": "sts-secret",
+                "sts_token": "token"
+            },
+            {
+                "name": "ecs",
+                "mode": "EcsRamRole",
+                "ram_role_name": "EcsRamRoleTest"
+            },
+            {
+                "name": "saml",
+                "mode": "External"
+            }
+        ],
+        "meta_path": ""
+    }"#;
+
+    fn write_config() -> PathBuf {
+        let path = env::temp_dir().join(format!("aliyun-config-{}.json", Uuid::new_v4()));
+        std::fs::write(&path, CONFIG).unwrap();
+        path
+    }
+
+    #[tokio::test]
+    async fn test_profiles() {
+        let path = write_config();
+        let provider = ProfileCredentialsProvider::new().with_path(&path);
+
+        let credentials = provider
+            .clone()
+            .with_profile_name("default")
+            .provide_credentials()
+            .await
+            .unwrap();
+        assert_eq!(
+            credentials,
+            Credentials::new("default-id".to_string(), "default-secret".to_string())
+        );
+
+        let credentials = provider
+            .clone()
+            .with_profile_name("sts")
+            .provide_credentials()
+            .await
+            .unwrap();
+        assert_eq!(credentials.security_token.as_deref(), Some("token"));
+
+        assert_matches!(
+            provider
+                .clone()
+                .with_profile_name("saml")
+                .provide_credentials()
+                .await,
+            Err(CredentialsError::InvalidProfile(_))
+        );
+        assert_matches!(
+            provider
+                .with_profile_name("missing")
+                .provide_credentials()
+                .await,
+            Err(CredentialsError::InvalidProfile(_))
+        );
+
+        std::fs::remove_file(path).unwrap();
+    }
+
+    #[tokio::test]
+    async fn test_missing_file_is_not_configured() {
+        let provider = ProfileCredentialsProvider::new()
+            .with_path(env::temp_dir().join(format!("missing-{}.json", Uuid::new_v4())));
+        assert_matches!(
+            provider.provide_credentials().await,
+            Err(CredentialsError::NotConfigured(_))
+        );
+    }
+
+    #[tokio::test]
+    async fn test_ecs_ram_role_provider_is_reused() {
+        let server = StubServer::start(|request: &StubRequest| {
+            match (request.method.as_str(), request.path.as_str()) {
+                ("PUT", "/latest/api/token") => (200, "metadata-token".to_string()),
+                (_, "/latest/meta-data/ram/security-credentials/EcsRamRoleTest") => {
+                    let body = serde_json::json!({
+                        "AccessKeyId": "STS.id",
+                        "AccessKeySecret": "secret",
+                        "Expiration": (Utc::now() + TimeDelta::hours(6))
+                            .format("%Y-%m-%dT%H:%M:%SZ")
+                            .to_string(),
+                        "SecurityToken": "token",
+                        "Code": "Success"
+                    });
+                    (200, body.to_string())
+                }
+                _ => (404, String::new()),
+            }
+        })
+        .await;
+        let path = write_config();
+        let provider = ProfileCredentialsProvider::new()
+            .with_path(&path)
+            .with_profile_name("ecs")
+            .with_metadata_base_url(server.url());
+
+        let credentials = provider.provide_credentials().await.unwrap();
+        assert_eq!(credentials.access_key_id, "STS.id");
+        // The token and the credentials are fetched once, then served from the cache.
+        provider.clone().provide_credentials().await.unwrap();
+        assert_eq!(server.requests().len(), 2);
+
+        std::fs::remove_file(path).unwrap();
+    }
+}
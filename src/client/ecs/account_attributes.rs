@@ -0,0 +1,33 @@
+use serde::{Deserialize, Serialize};
+
+use crate::client::utils::unwrap_list;
+
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+#[serde(rename_all = "PascalCase")]
+pub struct AttributeValue {
+    /// The value of the quota, e.g. `100` or `vpc`; the numeric quotas are strings too.
+    pub value: Option<String>,
+    pub count: Option<i64>,
+    pub zone_id: Option<String>,
+    pub instance_charge_type: Option<String>,
+    pub instance_type: Option<String>,
+    pub disk_category: Option<String>,
+    pub expired_time: Option<String>,
+}
+
+/// A quota of the account, e.g. `max-security-groups` or `instance-network-type`.
+#[derive(Deserialize, Debug, Clone, PartialEq)]
+#[serde(rename_all = "PascalCase")]
+pub struct AccountAttribute {
+    pub attribute_name: String,
+    #[serde(default, deserialize_with = "unwrap_list")]
+    pub attribute_values: Vec<AttributeValue>,
+}
+
+#[derive(Deserialize, Debug)]
+#[serde(rename_all = "PascalCase")]
+pub struct DescribeAccountAttributesBody {
+    pub request_id: String,
+    #[serde(default, deserialize_with = "unwrap_list")]
+    pub account_attribute_items: Vec<AccountAttribute>,
+}
@@ -0,0 +1,50 @@
+use serde::{Deserialize, Serialize};
+
+use crate::client::utils::unwrap_list;
+
+/// A resource, e.g. an instance type, and its stock.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+#[serde(rename_all = "PascalCase")]
+pub struct SupportedResource {
+    pub value: String,
+    /// `Available` or `SoldOut`.
+    pub status: Option<String>,
+    /// `WithStock`, `ClosedWithStock`, `WithoutStock` or `ClosedWithoutStock`.
+    pub status_category: Option<String>,
+    pub min: Option<i64>,
+    pub max: Option<i64>,
+    pub unit: Option<String>,
+}
+
+/// The resources of one type, e.g. `InstanceType` or `SystemDisk`.
+#[derive(Deserialize, Debug, Clone, PartialEq)]
+#[serde(rename_all = "PascalCase")]
+pub struct AvailableResource {
+    #[serde(rename = "Type")]
+    pub resource_type: String,
+    #[serde(default, deserialize_with = "unwrap_list")]
+    pub supported_resources: Vec<SupportedResource>,
+}
+
+#[derive(Deserialize, Debug, Clone, PartialEq)]
+#[serde(rename_all = "PascalCase")]
+pub struct AvailableZone {
+    pub region_id: Option<String>,
+    pub zone_id: String,
+    pub status: Option<String>,
+    pub status_category: Option<String>,
+    #[serde(default, deserialize_with = "unwrap_list")]
+    pub available_resources: Vec<AvailableResource>,
+}
+
+#[derive(Deserialize, Debug)]
+#[serde(rename_all = "PascalCase")]
+pub struct DescribeAvailableResourceBody {
+    pub request_id: String,
+    #[serde(default, deserialize_with = "unwrap_list")]
+    pub available_zones: Vec<AvailableZone>,
+}
+
+/// `DescribeResourcesModification` answers with the same structure as
+/// `DescribeAvailableResource`.
+pub type DescribeResourcesModificationBody = DescribeAvailableResourceBody;
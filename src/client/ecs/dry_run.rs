@@ -0,0 +1,298 @@
+//! Dry runs of the mutating ECS operations, checking the permissions, parameters and
+//! stock of a request without performing it.
+//!
+//! Aliyun answers a dry run that would succeed with the `DryRunOperation` error code; the
+//! `EcsClient::dry_run_*` methods return it as `DryRunResult::WouldSucceed`, and the
+//! other rejections as `DryRunResult::WouldFail`. Errors unrelated to the checks
+//! (transport, credentials...) are still returned as `Err`, and so is a successful answer,
+//! which means the operation was performed (`AdvancedClientError::DryRunPerformed`).
+
+use serde_json::Value;
+
+use crate::client::{
+    ecs::{operations::OperationBody, requests::RunInstancesRequest, EcsClient},
+    error::{AdvancedClientError, AliyunRejection},
+    utils::parse_json_value,
+};
+use crate::services::ecs;
+
+/// The error code of a dry run whose checks passed.
+pub const DRY_RUN_SUCCESS_CODE: &str = "DryRunOperation";
+
+/// Why a dry run failed, derived from the error code.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum DryRunFailureKind {
+    /// The caller is not allowed to perform the operation (`Forbidden.*`, `NoPermission`).
+    PermissionDenied,
+    /// A parameter is missing or invalid (`InvalidParameter`, `MissingParameter`...).
+    InvalidParameter,
+    /// The resource is in a state that does not allow the operation
+    /// (`IncorrectInstanceStatus`...).
+    IncorrectState,
+    /// A quota or the stock is insufficient (`QuotaExceed.*`, `*.NoStock`).
+    InsufficientResources,
+    Other,
+}
+
+impl DryRunFailureKind {
+    fn from_code(code: &str) -> Self {
+        if code.starts_with("Forbidden") || code.starts_with("NoPermission") {
+            DryRunFailureKind::PermissionDenied
+        } else if code.starts_with("Incorrect") {
+            DryRunFailureKind::IncorrectState
+        } else if code.starts_with("QuotaExceed") || code.ends_with("NoStock") {
+            DryRunFailureKind::InsufficientResources
+        } else if code.starts_with("Invalid") || code.starts_with("MissingParameter") {
+            DryRunFailureKind::InvalidParameter
+        } else {
+            DryRunFailureKind::Other
+        }
+    }
+}
+
+/// The outcome of the checks of a dry run.
+#[derive(Debug)]
+pub enum DryRunResult {
+    /// The request passed every check and would be performed.
+    WouldSucceed,
+    /// The request was rejected by a check.
+    WouldFail {
+        kind: DryRunFailureKind,
+        rejection: AliyunRejection,
+    },
+}
+
+impl DryRunResult {
+    pub fn would_succeed(&self) -> bool {
+        matches!(self, DryRunResult::WouldSucceed)
+    }
+
+    fn from_response(
+        response: Result<Value, AdvancedClientError>,
+    ) -> Result<Self, AdvancedClientError> {
+        let rejection = match response.and_then(parse_json_value::<OperationBody>) {
+            // The operation was performed: never report it as a mere check.
+            Ok(body) => {
+                return Err(AdvancedClientError::DryRunPerformed {
+                    request_id: body.request_id,
+                })
+            }
+            Err(AdvancedClientError::AliyunRejectError(rejection)) => rejection,
+            Err(err) => return Err(err),
+        };
+        if rejection.code == DRY_RUN_SUCCESS_CODE {
+            Ok(DryRunResult::WouldSucceed)
+        } else {
+            Ok(DryRunResult::WouldFail {
+                kind: DryRunFailureKind::from_code(&rejection.code),
+                rejection,
+            })
+        }
+    }
+}
+
+impl EcsClient {
+    /// Check a `RunInstances` request without creating anything.
+    pub async fn dry_run_run_instances(
+        &self,
+        request: &RunInstancesRequest,
+    ) -> Result<DryRunResult, AdvancedClientError> {
+        let request = request.clone().dry_run(true);
+        DryRunResult::from_response(
+            ecs::run_instances(self.client(), self.region_id(), &request).await,
+        )
+    }
+
+    /// Check a `StartInstances` request without starting the instances.
+    pub async fn dry_run_start_instances(
+        &self,
+        instance_ids: &[&str],
+    ) -> Result<DryRunResult, AdvancedClientError> {
+        DryRunResult::from_response(
+            ecs::start_instances_with_dry_run(self.client(), instance_ids.to_vec(), Some(true))
+                .await,
+        )
+    }
+
+    /// Check a `StopInstances` request without stopping the instances.
+    pub async fn dry_run_stop_instances(
+        &self,
+        instance_ids: &[&str],
+        force_stop: Option<bool>,
+    ) -> Result<DryRunResult, AdvancedClientError> {
+        DryRunResult::from_response(
+            ecs::stop_instances(self.client(), instance_ids.to_vec(), force_stop, Some(true)).await,
+        )
+    }
+
+    /// Check a `RebootInstance` request without rebooting the instance.
+    pub async fn dry_run_reboot_instance(
+        &self,
+        instance_id: &str,
+        force_stop: Option<bool>,
+    ) -> Result<DryRunResult, AdvancedClientError> {
+        DryRunResult::from_response(
+            ecs::reboot_instance(self.client(), instance_id, force_stop, Some(true)).await,
+        )
+    }
+
+    /// Check a `DeleteInstance` request without releasing the instance.
+    pub async fn dry_run_delete_instance(
+        &self,
+        instance_id: &str,
+    ) -> Result<DryRunResult, AdvancedClientError> {
+        DryRunResult::from_response(
+            ecs::delete_instance_with_dry_run(self.client(), instance_id, Some(true)).await,
+        )
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    use crate::client::transport::{HttpResponse, MockTransport};
+    use crate::client::AliyunClient;
+    use crate::test_utils::http_stub::{StubRequest, StubServer};
+
+    fn rejection(code: &str) -> Value {
+        json!({
+            "RequestId": "REQ",
+            "HostId": "ecs.cn-hangzhou.aliyuncs.com",
+            "Code": code,
+            "Message": "message"
+        })
+    }
+
+    /// The result of `send_request` for a dry run answered with an error status.
+    fn rejected(code: &str) -> Result<Value, AdvancedClientError> {
+        let rejection = serde_json::from_value(rejection(code)).unwrap();
+        Err(AdvancedClientError::AliyunRejectError(rejection))
+    }
+
+    #[test]
+    fn test_from_response() {
+        let result = DryRunResult::from_response(rejected("DryRunOperation")).unwrap();
+        assert!(result.would_succeed());
+
+        for (code, expected) in [
+            ("Forbidden.RAM", DryRunFailureKind::PermissionDenied),
+            ("InvalidParameter", DryRunFailureKind::InvalidParameter),
+            ("IncorrectInstanceStatus", DryRunFailureKind::IncorrectState),
+            (
+                "OperationDenied.NoStock",
+                DryRunFailureKind::InsufficientResources,
+            ),
+            ("UnknownError", DryRunFailureKind::Other),
+        ] {
+            match DryRunResult::from_response(rejected(code)).unwrap() {
+                DryRunResult::WouldFail { kind, rejection } => {
+                    assert_eq!(kind, expected);
+                    assert_eq!(rejection.code, code);
+                }
+                DryRunResult::WouldSucceed => panic!("{} should fail", code),
+            }
+        }
+
+        let transport = AdvancedClientError::Signature("boom".to_string());
+        assert!(DryRunResult::from_response(Err(transport)).is_err());
+    }
+
+    #[test]
+    fn test_performed_dry_run_is_an_error() {
+        let result = DryRunResult::from_response(Ok(json!({"RequestId": "REQ"})));
+        assert!(matches!(
+            result,
+            Err(AdvancedClientError::DryRunPerformed { ref request_id }) if request_id == "REQ"
+        ));
+    }
+
+    #[tokio::test]
+    async fn test_dry_run_sends_dry_run() {
+        // Aliyun answers the dry runs with HTTP 400.
+        let server = StubServer::start(|request: &StubRequest| {
+            let code = if request.path.contains("DryRun=true") {
+                "DryRunOperation"
+            } else {
+                "MissingParameter"
+            };
+            (400, rejection(code).to_string())
+        })
+        .await;
+        let client = AliyunClient::builder()
+            .credentials("id".to_string(), "secret".to_string())
+            .endpoint_override("ecs", server.url())
+            .build()
+            .unwrap();
+        let ecs = client.ecs("cn-hangzhou");
+
+        let result = ecs.dry_run_stop_instances(&["i-1"], None).await.unwrap();
+        assert!(result.would_succeed());
+        let request = RunInstancesRequest::new("img-1", "ecs.g7.large");
+        let result = ecs.dry_run_run_instances(&request).await.unwrap();
+        assert!(result.would_succeed());
+        assert_eq!(server.requests().len(), 2);
+    }
+
+    #[tokio::test]
+    async fn test_every_dry_run_sends_dry_run() {
+        let transport = MockTransport::with_handler(|_| {
+            Ok(HttpResponse::new(
+                400,
+                rejection(DRY_RUN_SUCCESS_CODE).to_string(),
+            ))
+        });
+        let client = AliyunClient::builder()
+            .credentials("id".to_string(), "secret".to_string())
+            .transport(transport.clone())
+            .build()
+            .unwrap();
+        let ecs = client.ecs("cn-hangzhou");
+
+        assert!(ecs
+            .dry_run_start_instances(&["i-1"])
+            .await
+            .unwrap()
+            .would_succeed());
+        assert!(ecs
+            .dry_run_reboot_instance("i-1", Some(true))
+            .await
+            .unwrap()
+            .would_succeed());
+        assert!(ecs
+            .dry_run_delete_instance("i-1")
+            .await
+            .unwrap()
+            .would_succeed());
+
+        let actions: Vec<String> = transport
+            .requests()
+            .iter()
+            .map(|request| {
+                let params = request.query_params();
+                assert_eq!(params["DryRun"], "true");
+                params["Action"].clone()
+            })
+            .collect();
+        assert_eq!(
+            actions,
+            ["StartInstances", "RebootInstance", "DeleteInstance"]
+        );
+
+        // The operations themselves never send `DryRun`.
+        let transport = MockTransport::new();
+        transport.push_response(HttpResponse::json(&json!({"RequestId": "REQ"})));
+        transport.push_response(HttpResponse::json(&json!({"RequestId": "REQ"})));
+        let ecs = client
+            .clone()
+            .with_transport(transport.clone())
+            .ecs("cn-hangzhou");
+        ecs.start_instances(&["i-1"]).await.unwrap();
+        ecs.delete_instance("i-1").await.unwrap();
+        assert!(transport
+            .requests()
+            .iter()
+            .all(|request| !request.query_params().contains_key("DryRun")));
+    }
+}
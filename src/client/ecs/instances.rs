@@ -0,0 +1,176 @@
+use serde::{Deserialize, Serialize};
+
+use crate::client::{paginator::PaginatedBody, utils::unwrap_list};
+
+/// The lifecycle state of an instance.
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Eq)]
+pub enum InstanceState {
+    Pending,
+    Running,
+    Starting,
+    Stopping,
+    Stopped,
+    /// A state added after this version of the crate.
+    #[serde(other)]
+    Unknown,
+}
+
+impl InstanceState {
+    /// The name of the state on the wire, e.g. `Running`. `Unknown` is not an Aliyun
+    /// state and cannot be a filter, see `InstanceStatusFilter`.
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            InstanceState::Pending => "Pending",
+            InstanceState::Running => "Running",
+            InstanceState::Starting => "Starting",
+            InstanceState::Stopping => "Stopping",
+            InstanceState::Stopped => "Stopped",
+            InstanceState::Unknown => "Unknown",
+        }
+    }
+}
+
+impl std::fmt::Display for InstanceState {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+#[derive(Deserialize, Debug, Clone, PartialEq)]
+#[serde(rename_all = "PascalCase")]
+pub struct VpcAttributes {
+    pub vpc_id: Option<String>,
+    #[serde(rename = "VSwitchId")]
+    pub vswitch_id: Option<String>,
+    #[serde(default, deserialize_with = "unwrap_list")]
+    pub private_ip_address: Vec<String>,
+    pub nat_ip_address: Option<String>,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+#[serde(rename_all = "PascalCase")]
+pub struct EipAddress {
+    pub ip_address: Option<String>,
+    pub allocation_id: Option<String>,
+    pub bandwidth: Option<u32>,
+    pub internet_charge_type: Option<String>,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+#[serde(rename_all = "PascalCase")]
+pub struct Tag {
+    pub tag_key: String,
+    pub tag_value: Option<String>,
+}
+
+/// An instance, as returned by `DescribeInstances`.
+///
+/// Times are kept as returned by Aliyun (e.g. `2017-12-10T04:04Z`), as their precision
+/// varies between fields.
+#[derive(Deserialize, Debug, Clone, PartialEq)]
+#[serde(rename_all = "PascalCase")]
+pub struct Instance {
+    pub instance_id: String,
+    pub instance_name: Option<String>,
+    pub description: Option<String>,
+    pub region_id: Option<String>,
+    pub zone_id: Option<String>,
+    pub status: Option<InstanceState>,
+    pub instance_type: Option<String>,
+    pub instance_type_family: Option<String>,
+    pub cpu: Option<u32>,
+    /// The memory in MiB.
+    pub memory: Option<u64>,
+    pub image_id: Option<String>,
+    pub host_name: Option<String>,
+    #[serde(rename = "OSName")]
+    pub os_name: Option<String>,
+    #[serde(rename = "OSType")]
+    pub os_type: Option<String>,
+    pub instance_charge_type: Option<String>,
+    pub internet_charge_type: Option<String>,
+    pub spot_strategy: Option<String>,
+    pub instance_network_type: Option<String>,
+    pub creation_time: Option<String>,
+    pub start_time: Option<String>,
+    pub expired_time: Option<String>,
+    pub key_pair_name: Option<String>,
+    pub resource_group_id: Option<String>,
+    pub deletion_protection: Option<bool>,
+    pub vpc_attributes: Option<VpcAttributes>,
+    pub eip_address: Option<EipAddress>,
+    #[serde(default, deserialize_with = "unwrap_list")]
+    pub public_ip_address: Vec<String>,
+    #[serde(default, deserialize_with = "unwrap_list")]
+    pub inner_ip_address: Vec<String>,
+    #[serde(default, deserialize_with = "unwrap_list")]
+    pub security_group_ids: Vec<String>,
+    #[serde(default, deserialize_with = "unwrap_list")]
+    pub tags: Vec<Tag>,
+}
+
+#[derive(Deserialize, Debug)]
+#[serde(rename_all = "PascalCase")]
+pub struct DescribeInstancesBody {
+    pub request_id: String,
+    pub total_count: Option<u32>,
+    pub page_number: Option<u32>,
+    pub page_size: Option<u32>,
+    pub next_token: Option<String>,
+    #[serde(default, deserialize_with = "unwrap_list")]
+    pub instances: Vec<Instance>,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+#[serde(rename_all = "PascalCase")]
+pub struct InstanceStatus {
+    pub instance_id: String,
+    pub status: InstanceState,
+}
+
+#[derive(Deserialize, Debug)]
+#[serde(rename_all = "PascalCase")]
+pub struct DescribeInstanceStatusBody {
+    pub request_id: String,
+    pub total_count: Option<u32>,
+    pub page_number: Option<u32>,
+    pub page_size: Option<u32>,
+    #[serde(default, deserialize_with = "unwrap_list")]
+    pub instance_statuses: Vec<InstanceStatus>,
+}
+
+impl PaginatedBody for DescribeInstancesBody {
+    type Item = Instance;
+
+    fn total_count(&self) -> Option<u32> {
+        self.total_count
+    }
+
+    fn next_token(&self) -> Option<&str> {
+        self.next_token.as_deref()
+    }
+
+    fn item_count(&self) -> usize {
+        self.instances.len()
+    }
+
+    fn into_items(self) -> Vec<Instance> {
+        self.instances
+    }
+}
+
+impl PaginatedBody for DescribeInstanceStatusBody {
+    type Item = InstanceStatus;
+
+    fn total_count(&self) -> Option<u32> {
+        self.total_count
+    }
+
+    fn item_count(&self) -> usize {
+        self.instance_statuses.len()
+    }
+
+    fn into_items(self) -> Vec<InstanceStatus> {
+        self.instance_statuses
+    }
+}
@@ -0,0 +1,662 @@
+//! The ECS client and the typed response models of the ECS operations in
+//! `services::ecs`, parsed with `parse_json_value`.
+//!
+//! The lists wrapped in an object by Aliyun (e.g. `"Regions": {"Region": [...]}`) are
+//! unwrapped, fields missing from some responses are `Option`s, and unknown fields are
+//! ignored so that new response fields don't break deserialization.
+
+pub mod account_attributes;
+pub mod available_resource;
+pub mod dry_run;
+pub mod instances;
+pub mod operations;
+pub mod recommend_instance_type;
+pub mod regions;
+pub mod requests;
+pub mod waiters;
+pub mod zones;
+
+use futures_util::future::BoxFuture;
+
+use crate::{
+    client::{
+        ecs::{
+            account_attributes::DescribeAccountAttributesBody,
+            available_resource::{
+                DescribeAvailableResourceBody, DescribeResourcesModificationBody,
+            },
+            instances::{DescribeInstanceStatusBody, DescribeInstancesBody},
+            operations::{
+                DeleteInstanceBody, RebootInstanceBody, RunInstancesBody, StartInstancesBody,
+                StopInstancesBody,
+            },
+            recommend_instance_type::DescribeRecommendInstanceTypeBody,
+            regions::DescribeRegionsBody,
+            requests::{DescribeInstancesRequest, RunInstancesRequest},
+            zones::DescribeZonesBody,
+        },
+        error::AdvancedClientError,
+        paginator::{PageRequest, PaginationStyle, Paginator},
+        utils::parse_json_value,
+        AliyunClient,
+    },
+    services::ecs,
+};
+
+/// A client for ECS (Elastic Compute Service) operations in one region.
+///
+/// `EcsClient` holds a clone of the parent `AliyunClient` whose default region is the
+/// region of the `EcsClient`, so every operation is sent to the regional endpoint with
+/// the right `RegionId` without passing it to each call. Cloning an `AliyunClient` is
+/// cheap: the clone shares the HTTP client and the credentials provider.
+#[derive(Debug, Clone)]
+pub struct EcsClient {
+    client: AliyunClient,
+}
+
+impl EcsClient {
+    /// Create a new `EcsClient` sending the requests of `client` to `region_id`.
+    pub fn new(client: &AliyunClient, region_id: impl Into<String>) -> Self {
+        Self {
+            client: client.clone().with_default_region(region_id),
+        }
+    }
+
+    /// The region of the operations.
+    pub fn region_id(&self) -> &str {
+        self.client.default_region().unwrap_or_default()
+    }
+
+    /// The client the requests are sent with.
+    pub fn client(&self) -> &AliyunClient {
+        &self.client
+    }
+
+    /// Call the ECS `DescribeRegions` operation and return the regions available to the
+    /// account.
+    pub async fn describe_regions(&self) -> Result<DescribeRegionsBody, AdvancedClientError> {
+        let response = ecs::describe_regions(&self.client, Some(self.region_id())).await?;
+        parse_json_value(response)
+    }
+
+    /// Call the ECS `DescribeZones` operation and return the zones of the region.
+    pub async fn describe_zones(&self) -> Result<DescribeZonesBody, AdvancedClientError> {
+        let response = ecs::describe_zones(&self.client, self.region_id()).await?;
+        parse_json_value(response)
+    }
+
+    /// Call the ECS `DescribeAvailableResource` operation and return the resource stock of
+    /// the zone.
+    pub async fn describe_available_resource(
+        &self,
+        zone_id: &str,
+    ) -> Result<DescribeAvailableResourceBody, AdvancedClientError> {
+        let response =
+            ecs::describe_available_resource(&self.client, self.region_id(), zone_id).await?;
+        parse_json_value(response)
+    }
+
+    /// Call the ECS `DescribeAccountAttributes` operation and return the quotas of the
+    /// account in the region.
+    pub async fn describe_account_attributes(
+        &self,
+    ) -> Result<DescribeAccountAttributesBody, AdvancedClientError> {
+        let response = ecs::describe_account_attributes(&self.client).await?;
+        parse_json_value(response)
+    }
+
+    /// Call the ECS `DescribeResourcesModification` operation and return the resources
+    /// available to change the configuration of instances in the zone.
+    pub async fn describe_resources_modification(
+        &self,
+        zone_id: &str,
+    ) -> Result<DescribeResourcesModificationBody, AdvancedClientError> {
+        let response =
+            ecs::describe_resources_modification(&self.client, self.region_id(), zone_id).await?;
+        parse_json_value(response)
+    }
+
+    /// Call the ECS `DescribeRecommendInstanceType` operation and return the recommended
+    /// instance types of the region.
+    pub async fn describe_recommend_instance_type(
+        &self,
+    ) -> Result<DescribeRecommendInstanceTypeBody, AdvancedClientError> {
+        let response =
+            ecs::describe_recommend_instance_type(&self.client, self.region_id()).await?;
+        parse_json_value(response)
+    }
+
+    /// Call the ECS `RunInstances` operation and return the IDs of the created instances
+    /// in `instance_id_sets`.
+    pub async fn run_instances(
+        &self,
+        request: &RunInstancesRequest,
+    ) -> Result<RunInstancesBody, AdvancedClientError> {
+        let response = ecs::run_instances(&self.client, self.region_id(), request).await?;
+        parse_json_value(response)
+    }
+
+    /// Call the ECS `StartInstances` operation and return the outcome for each instance.
+    pub async fn start_instances(
+        &self,
+        instance_ids: &[&str],
+    ) -> Result<StartInstancesBody, AdvancedClientError> {
+        let response = ecs::start_instances(&self.client, instance_ids.to_vec()).await?;
+        parse_json_value(response)
+    }
+
+    /// Call the ECS `StopInstances` operation and return the outcome for each instance.
+    /// See `dry_run_stop_instances` to only check the request.
+    pub async fn stop_instances(
+        &self,
+        instance_ids: &[&str],
+        force_stop: Option<bool>,
+    ) -> Result<StopInstancesBody, AdvancedClientError> {
+        let response =
+            ecs::stop_instances(&self.client, instance_ids.to_vec(), force_stop, None).await?;
+        parse_json_value(response)
+    }
+
+    /// Call the ECS `RebootInstance` operation. See `dry_run_reboot_instance` to only check
+    /// the request.
+    pub async fn reboot_instance(
+        &self,
+        instance_id: &str,
+        force_stop: Option<bool>,
+    ) -> Result<RebootInstanceBody, AdvancedClientError> {
+        let response = ecs::reboot_instance(&self.client, instance_id, force_stop, None).await?;
+        parse_json_value(response)
+    }
+
+    /// Call the ECS `DeleteInstance` operation.
+    pub async fn delete_instance(
+        &self,
+        instance_id: &str,
+    ) -> Result<DeleteInstanceBody, AdvancedClientError> {
+        let response = ecs::delete_instance(&self.client, instance_id).await?;
+        parse_json_value(response)
+    }
+
+    /// Call the ECS `DescribeInstanceStatus` operation and return one page of instance
+    /// statuses.
+    pub async fn describe_instance_status(
+        &self,
+        instance_id: Option<&str>,
+        page_number: Option<u32>,
+        page_size: Option<u32>,
+    ) -> Result<DescribeInstanceStatusBody, AdvancedClientError> {
+        let response = ecs::describe_instance_status(
+            &self.client,
+            self.region_id(),
+            instance_id,
+            page_number,
+            page_size,
+        )
+        .await?;
+        parse_json_value(response)
+    }
+
+    /// Call the ECS `DescribeInstanceStatus` operation for the given instances (at most
+    /// 100) and return their statuses in one page. Released instances are not listed.
+    pub async fn describe_instance_status_by_ids(
+        &self,
+        instance_ids: &[&str],
+    ) -> Result<DescribeInstanceStatusBody, AdvancedClientError> {
+        let response =
+            ecs::describe_instance_status_by_ids(&self.client, self.region_id(), instance_ids)
+                .await?;
+        parse_json_value(response)
+    }
+
+    /// Call the ECS `DescribeInstances` operation and return one page of the instances
+    /// matching `request`.
+    pub async fn describe_instances(
+        &self,
+        request: &DescribeInstancesRequest,
+    ) -> Result<DescribeInstancesBody, AdvancedClientError> {
+        let response = ecs::describe_instances(&self.client, self.region_id(), request).await?;
+        parse_json_value(response)
+    }
+
+    /// Page through the `DescribeInstanceStatus` operation, e.g.
+    /// `ecs.describe_instance_status_paginator(None).page_size(100).collect_all().await`.
+    pub fn describe_instance_status_paginator<'a>(
+        &'a self,
+        instance_id: Option<&'a str>,
+    ) -> Paginator<
+        impl FnMut(
+                PageRequest,
+            )
+                -> BoxFuture<'a, Result<DescribeInstanceStatusBody, AdvancedClientError>>
+            + 'a,
+    > {
+        Paginator::new(PaginationStyle::PageNumber, move |request: PageRequest| {
+            Box::pin(self.describe_instance_status(
+                instance_id,
+                request.page_number,
+                Some(request.page_size),
+            )) as BoxFuture<'a, _>
+        })
+    }
+
+    /// Page through the `DescribeInstances` operation, yielding every instance matching
+    /// `filters`. The paging of `filters` is replaced by the paginator's.
+    pub fn describe_instances_paginator<'a>(
+        &'a self,
+        filters: DescribeInstancesRequest,
+    ) -> Paginator<
+        impl FnMut(PageRequest) -> BoxFuture<'a, Result<DescribeInstancesBody, AdvancedClientError>>
+            + 'a,
+    > {
+        Paginator::new(PaginationStyle::PageNumber, move |page: PageRequest| {
+            let request = filters
+                .clone()
+                .page_number(page.page_number.unwrap_or(1))
+                .page_size(page.page_size);
+            Box::pin(async move { self.describe_instances(&request).await }) as BoxFuture<'a, _>
+        })
+    }
+}
+
+impl AliyunClient {
+    /// Obtain an `EcsClient` sending the ECS requests of this client to `region_id`.
+    ///
+    /// This convenience method enables method chaining like
+    /// `client.ecs("cn-hangzhou").describe_regions().await`.
+    pub fn ecs(&self, region_id: impl Into<String>) -> EcsClient {
+        EcsClient::new(self, region_id)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use serde_json::json;
+    use std::collections::HashMap;
+
+    use crate::test_utils::http_stub::{StubRequest, StubServer};
+
+    use crate::client::{
+        ecs::{
+            account_attributes::DescribeAccountAttributesBody,
+            available_resource::{
+                DescribeAvailableResourceBody, DescribeResourcesModificationBody,
+            },
+            instances::{DescribeInstanceStatusBody, DescribeInstancesBody, InstanceState},
+            operations::{DeleteInstanceBody, RunInstancesBody, StartInstancesBody},
+            recommend_instance_type::DescribeRecommendInstanceTypeBody,
+            regions::DescribeRegionsBody,
+            requests::{DescribeInstancesRequest, InstanceStatusFilter, RunInstancesRequest},
+            zones::DescribeZonesBody,
+        },
+        error::AdvancedClientError,
+        utils::parse_json_value,
+        AliyunClient,
+    };
+
+    fn query(request: &StubRequest) -> HashMap<String, String> {
+        let (_, query) = request.path.split_once('?').unwrap();
+        query
+            .split('&')
+            .filter_map(|pair| pair.split_once('='))
+            .map(|(k, v)| (k.to_string(), v.to_string()))
+            .collect()
+    }
+
+    #[tokio::test]
+    async fn test_ecs_client_sends_region() {
+        let server = StubServer::start(|request: &StubRequest| {
+            let body = match query(request)["Action"].as_str() {
+                "DescribeInstanceStatus" => json!({
+                    "RequestId": "REQ",
+                    "TotalCount": 1,
+                    "InstanceStatuses": {"InstanceStatus": [{"InstanceId": "i-1", "Status": "Running"}]}
+                }),
+                _ => json!({
+                    "RequestId": "REQ",
+                    "InstanceResponses": {"InstanceResponse": [{"InstanceId": "i-1", "CurrentStatus": "Stopping"}]}
+                }),
+            };
+            (200, body.to_string())
+        })
+        .await;
+        let client = AliyunClient::builder()
+            .credentials("id".to_string(), "secret".to_string())
+            .default_region("cn-hangzhou")
+            .endpoint_override("ecs", server.url())
+            .build()
+            .unwrap();
+        let ecs = client.ecs("cn-shanghai");
+        assert_eq!(ecs.region_id(), "cn-shanghai");
+        assert_eq!(client.default_region(), Some("cn-hangzhou"));
+
+        let body = ecs
+            .describe_instance_status(None, None, None)
+            .await
+            .unwrap();
+        assert_eq!(body.instance_statuses[0].status, InstanceState::Running);
+        let body = ecs.stop_instances(&["i-1"], None).await.unwrap();
+        assert_eq!(body.instance_responses[0].instance_id, "i-1");
+
+        let requests = server.requests();
+        assert_eq!(query(&requests[0])["RegionId"], "cn-shanghai");
+        assert_eq!(query(&requests[1])["Action"], "StopInstances");
+        assert_eq!(query(&requests[1])["RegionId"], "cn-shanghai");
+    }
+
+    #[tokio::test]
+    async fn test_run_instances() {
+        let server = StubServer::start(|_: &StubRequest| {
+            let body = json!({
+                "RequestId": "REQ",
+                "InstanceIdSets": {"InstanceIdSet": ["i-1", "i-2"]}
+            });
+            (200, body.to_string())
+        })
+        .await;
+        let client = AliyunClient::builder()
+            .credentials("id".to_string(), "secret".to_string())
+            .endpoint_override("ecs", server.url())
+            .build()
+            .unwrap();
+
+        let request = RunInstancesRequest::new("img-1", "ecs.g7.large")
+            .vswitch_id("vsw-1")
+            .security_group_id("sg-1")
+            .amount(2);
+        let body = client
+            .ecs("cn-hangzhou")
+            .run_instances(&request)
+            .await
+            .unwrap();
+        assert_eq!(body.instance_id_sets, ["i-1", "i-2"]);
+
+        let query = query(&server.requests()[0]);
+        assert_eq!(query["VSwitchId"], "vsw-1");
+        assert_eq!(query["Amount"], "2");
+        assert!(!query["ClientToken"].is_empty());
+    }
+
+    #[tokio::test]
+    async fn test_describe_instances_paginator() {
+        let server = StubServer::start(|request: &StubRequest| {
+            let query = query(request);
+            let page_number: u32 = query["PageNumber"].parse().unwrap();
+            let instances: Vec<_> = (0..2)
+                .map(|i| json!({"InstanceId": format!("i-{}", (page_number - 1) * 2 + i)}))
+                .take(if page_number == 3 { 1 } else { 2 })
+                .collect();
+            let body = json!({
+                "RequestId": "REQ",
+                "TotalCount": 5,
+                "PageNumber": page_number,
+                "PageSize": 2,
+                "Instances": {"Instance": instances}
+            });
+            (200, body.to_string())
+        })
+        .await;
+        let client = AliyunClient::builder()
+            .credentials("id".to_string(), "secret".to_string())
+            .endpoint_override("ecs", server.url())
+            .build()
+            .unwrap();
+
+        let instances = client
+            .ecs("cn-hangzhou")
+            .describe_instances_paginator(
+                DescribeInstancesRequest::new().status(InstanceStatusFilter::Running),
+            )
+            .page_size(2)
+            .collect_all()
+            .await
+            .unwrap();
+        let ids: Vec<_> = instances.iter().map(|i| i.instance_id.as_str()).collect();
+        assert_eq!(ids, ["i-0", "i-1", "i-2", "i-3", "i-4"]);
+
+        let requests = server.requests();
+        assert_eq!(requests.len(), 3);
+        assert_eq!(query(&requests[2])["PageSize"], "2");
+        assert_eq!(query(&requests[2])["Status"], "Running");
+    }
+
+    #[test]
+    fn test_describe_regions_body() {
+        let body = parse_json_value::<DescribeRegionsBody>(json!({
+            "RequestId": "REQ",
+            "Regions": {"Region": [
+                {"RegionId": "cn-qingdao", "RegionEndpoint": "ecs.cn-qingdao.aliyuncs.com", "LocalName": "华北1（青岛）", "Status": "available"},
+                {"RegionId": "cn-beijing", "LocalName": "华北2（北京）"}
+            ]}
+        }))
+        .unwrap();
+        assert_eq!(body.regions.len(), 2);
+        assert_eq!(body.regions[0].region_id, "cn-qingdao");
+        assert_eq!(body.regions[1].status, None);
+    }
+
+    #[test]
+    fn test_describe_zones_body() {
+        let body = parse_json_value::<DescribeZonesBody>(json!({
+            "RequestId": "REQ",
+            "Zones": {"Zone": [{
+                "ZoneId": "cn-hangzhou-i",
+                "LocalName": "杭州 可用区I",
+                "ZoneType": "AvailabilityZone",
+                "AvailableResourceCreation": {"ResourceTypes": ["Instance", "Disk"]},
+                "AvailableInstanceTypes": {"InstanceTypes": ["ecs.g7.large"]},
+                "AvailableDiskCategories": {"DiskCategories": ["cloud_essd"]},
+                "AvailableVolumeCategories": {"VolumeCategories": ["cloud_essd"]}
+            }]}
+        }))
+        .unwrap();
+        assert_eq!(
+            body.zones[0].available_resource_creation,
+            ["Instance", "Disk"]
+        );
+        assert_eq!(body.zones[0].available_instance_types, ["ecs.g7.large"]);
+    }
+
+    #[test]
+    fn test_describe_available_resource_body() {
+        let value = json!({
+            "RequestId": "REQ",
+            "AvailableZones": {"AvailableZone": [{
+                "RegionId": "cn-hangzhou",
+                "ZoneId": "cn-hangzhou-i",
+                "Status": "Available",
+                "StatusCategory": "WithStock",
+                "AvailableResources": {"AvailableResource": [{
+                    "Type": "InstanceType",
+                    "SupportedResources": {"SupportedResource": [
+                        {"Value": "ecs.g7.large", "Status": "Available", "StatusCategory": "WithStock"},
+                        {"Value": "cloud_essd", "Min": 20, "Max": 32768, "Unit": "GiB"}
+                    ]}
+                }]}
+            }]}
+        });
+        let body = parse_json_value::<DescribeAvailableResourceBody>(value.clone()).unwrap();
+        let resource = &body.available_zones[0].available_resources[0];
+        assert_eq!(resource.resource_type, "InstanceType");
+        assert_eq!(resource.supported_resources[1].max, Some(32768));
+
+        let body = parse_json_value::<DescribeResourcesModificationBody>(value).unwrap();
+        assert_eq!(body.available_zones[0].zone_id, "cn-hangzhou-i");
+    }
+
+    #[test]
+    fn test_describe_account_attributes_body() {
+        let body = parse_json_value::<DescribeAccountAttributesBody>(json!({
+            "RequestId": "REQ",
+            "AccountAttributeItems": {"AccountAttributeItem": [{
+                "AttributeName": "max-security-groups",
+                "AttributeValues": {"ValueItem": [{"Value": "100", "Count": 1}]}
+            }]}
+        }))
+        .unwrap();
+        let item = &body.account_attribute_items[0];
+        assert_eq!(item.attribute_name, "max-security-groups");
+        assert_eq!(item.attribute_values[0].value.as_deref(), Some("100"));
+    }
+
+    #[test]
+    fn test_describe_recommend_instance_type_body() {
+        let body = parse_json_value::<DescribeRecommendInstanceTypeBody>(json!({
+            "RequestId": "REQ",
+            "Data": {"RecommendInstanceType": [{
+                "RegionId": "cn-hangzhou",
+                "ZoneId": "cn-hangzhou-i",
+                "CommodityCode": "ecs",
+                "Scene": "CREATE",
+                "InstanceChargeType": "PostPaid",
+                "SpotStrategy": "NoSpot",
+                "NetworkType": "vpc",
+                "Priority": 1,
+                "InstanceType": {
+                    "InstanceType": "ecs.g7.large",
+                    "InstanceTypeFamily": "ecs.g7",
+                    "Generation": "ecs-6",
+                    "Cores": 2,
+                    "Memory": 8192,
+                    "SupportIoOptimized": "optimized"
+                },
+                "Zones": {"zone": []}
+            }]}
+        }))
+        .unwrap();
+        let recommended = &body.recommend_instance_types[0];
+        assert_eq!(recommended.instance_type.instance_type, "ecs.g7.large");
+        assert_eq!(recommended.instance_type.memory, Some(8192));
+    }
+
+    #[test]
+    fn test_describe_instances_body() {
+        let body = parse_json_value::<DescribeInstancesBody>(json!({
+            "RequestId": "REQ",
+            "TotalCount": 1,
+            "PageNumber": 1,
+            "PageSize": 10,
+            "Instances": {"Instance": [{
+                "InstanceId": "i-bp67acfmxazb4p****",
+                "InstanceName": "web-1",
+                "RegionId": "cn-hangzhou",
+                "ZoneId": "cn-hangzhou-i",
+                "Status": "Running",
+                "InstanceType": "ecs.g7.large",
+                "Cpu": 2,
+                "Memory": 8192,
+                "OSName": "Alibaba Cloud Linux 3",
+                "OSType": "linux",
+                "CreationTime": "2017-12-10T04:04Z",
+                "DeletionProtection": false,
+                "VpcAttributes": {
+                    "VpcId": "vpc-123",
+                    "VSwitchId": "vsw-123",
+                    "PrivateIpAddress": {"IpAddress": ["172.17.0.1"]},
+                    "NatIpAddress": ""
+                },
+                "EipAddress": {"IpAddress": "", "AllocationId": "", "InternetChargeType": ""},
+                "PublicIpAddress": {"IpAddress": []},
+                "SecurityGroupIds": {"SecurityGroupId": ["sg-123"]},
+                "Tags": {"Tag": [{"TagKey": "env", "TagValue": "prod"}]},
+                "NetworkInterfaces": {"NetworkInterface": []},
+                "SomeFieldAddedLater": {"Whatever": 1}
+            }]}
+        }))
+        .unwrap();
+        assert_eq!(body.total_count, Some(1));
+        let instance = &body.instances[0];
+        assert_eq!(instance.status, Some(InstanceState::Running));
+        assert_eq!(instance.os_name.as_deref(), Some("Alibaba Cloud Linux 3"));
+        assert_eq!(
+            instance.vpc_attributes.as_ref().unwrap().private_ip_address,
+            ["172.17.0.1"]
+        );
+        assert!(instance.public_ip_address.is_empty());
+        assert_eq!(instance.security_group_ids, ["sg-123"]);
+        assert_eq!(instance.tags[0].tag_value.as_deref(), Some("prod"));
+    }
+
+    #[test]
+    fn test_describe_instance_status_body() {
+        let body = parse_json_value::<DescribeInstanceStatusBody>(json!({
+            "RequestId": "REQ",
+            "TotalCount": 2,
+            "PageNumber": 1,
+            "PageSize": 50,
+            "InstanceStatuses": {"InstanceStatus": [
+                {"InstanceId": "i-1", "Status": "Stopped"},
+                {"InstanceId": "i-2", "Status": "Hibernated"}
+            ]}
+        }))
+        .unwrap();
+        assert_eq!(body.instance_statuses[0].status, InstanceState::Stopped);
+        assert_eq!(body.instance_statuses[1].status, InstanceState::Unknown);
+    }
+
+    #[test]
+    fn test_operation_bodies() {
+        let body = parse_json_value::<RunInstancesBody>(json!({
+            "RequestId": "REQ",
+            "OrderId": "1234567890",
+            "TradePrice": 0.165,
+            "InstanceIdSets": {"InstanceIdSet": ["i-1", "i-2"]}
+        }))
+        .unwrap();
+        assert_eq!(body.instance_id_sets, ["i-1", "i-2"]);
+
+        let body = parse_json_value::<StartInstancesBody>(json!({
+            "RequestId": "REQ",
+            "InstanceResponses": {"InstanceResponse": [{
+                "InstanceId": "i-1",
+                "Code": "200",
+                "Message": "success",
+                "PreviousStatus": "Stopped",
+                "CurrentStatus": "Starting"
+            }]}
+        }))
+        .unwrap();
+        assert_eq!(
+            body.instance_responses[0].current_status.as_deref(),
+            Some("Starting")
+        );
+
+        let body = parse_json_value::<DeleteInstanceBody>(json!({"RequestId": "REQ"})).unwrap();
+        assert_eq!(body.request_id, "REQ");
+    }
+
+    #[tokio::test]
+    async fn test_rejection_is_not_an_operation_body() {
+        let server = StubServer::start(|_: &StubRequest| {
+            let body = json!({
+                "RequestId": "REQ",
+                "HostId": "ecs.aliyuncs.com",
+                "Code": "IncorrectInstanceStatus",
+                "Message": "The current status of the resource does not support this operation."
+            });
+            (403, body.to_string())
+        })
+        .await;
+        let client = AliyunClient::builder()
+            .credentials("id".to_string(), "secret".to_string())
+            .endpoint_override("ecs", server.url())
+            .build()
+            .unwrap();
+
+        let result = client.ecs("cn-hangzhou").delete_instance("i-1").await;
+        assert!(matches!(
+            result,
+            Err(AdvancedClientError::AliyunRejectError(rejection)) if rejection.code == "IncorrectInstanceStatus"
+        ));
+    }
+
+    #[test]
+    fn test_success_body_with_code_is_not_a_rejection() {
+        let body = parse_json_value::<DeleteInstanceBody>(json!({
+            "RequestId": "REQ",
+            "HostId": "ecs.aliyuncs.com",
+            "Code": "200"
+        }))
+        .unwrap();
+        assert_eq!(body.request_id, "REQ");
+    }
+}
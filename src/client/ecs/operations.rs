@@ -0,0 +1,46 @@
+use serde::{Deserialize, Serialize};
+
+use crate::client::utils::unwrap_list;
+
+#[derive(Deserialize, Debug)]
+#[serde(rename_all = "PascalCase")]
+pub struct RunInstancesBody {
+    pub request_id: String,
+    #[serde(default, deserialize_with = "unwrap_list")]
+    pub instance_id_sets: Vec<String>,
+    pub order_id: Option<String>,
+    pub trade_price: Option<f64>,
+}
+
+/// The outcome of a batch operation for one instance.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+#[serde(rename_all = "PascalCase")]
+pub struct InstanceResponse {
+    pub instance_id: String,
+    pub code: Option<String>,
+    pub message: Option<String>,
+    pub previous_status: Option<String>,
+    pub current_status: Option<String>,
+}
+
+/// The response of the batch operations on instances.
+#[derive(Deserialize, Debug)]
+#[serde(rename_all = "PascalCase")]
+pub struct InstancesOperationBody {
+    pub request_id: String,
+    #[serde(default, deserialize_with = "unwrap_list")]
+    pub instance_responses: Vec<InstanceResponse>,
+}
+
+pub type StartInstancesBody = InstancesOperationBody;
+pub type StopInstancesBody = InstancesOperationBody;
+
+/// The response of the operations returning nothing but the request ID.
+#[derive(Serialize, Deserialize, Debug)]
+#[serde(rename_all = "PascalCase")]
+pub struct OperationBody {
+    pub request_id: String,
+}
+
+pub type RebootInstanceBody = OperationBody;
+pub type DeleteInstanceBody = OperationBody;
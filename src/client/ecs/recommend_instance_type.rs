@@ -0,0 +1,37 @@
+use serde::{Deserialize, Serialize};
+
+use crate::client::utils::unwrap_list;
+
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+#[serde(rename_all = "PascalCase")]
+pub struct InstanceTypeInfo {
+    pub instance_type: String,
+    pub instance_type_family: Option<String>,
+    pub generation: Option<String>,
+    pub cores: Option<u32>,
+    /// The memory in MiB.
+    pub memory: Option<u64>,
+    pub support_io_optimized: Option<String>,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+#[serde(rename_all = "PascalCase")]
+pub struct RecommendInstanceType {
+    pub region_id: Option<String>,
+    pub zone_id: Option<String>,
+    pub commodity_code: Option<String>,
+    pub scene: Option<String>,
+    pub instance_charge_type: Option<String>,
+    pub spot_strategy: Option<String>,
+    pub network_type: Option<String>,
+    pub priority: Option<i32>,
+    pub instance_type: InstanceTypeInfo,
+}
+
+#[derive(Deserialize, Debug)]
+#[serde(rename_all = "PascalCase")]
+pub struct DescribeRecommendInstanceTypeBody {
+    pub request_id: String,
+    #[serde(default, rename = "Data", deserialize_with = "unwrap_list")]
+    pub recommend_instance_types: Vec<RecommendInstanceType>,
+}
@@ -0,0 +1,21 @@
+use serde::{Deserialize, Serialize};
+
+use crate::client::utils::unwrap_list;
+
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+#[serde(rename_all = "PascalCase")]
+pub struct Region {
+    pub region_id: String,
+    pub local_name: Option<String>,
+    pub region_endpoint: Option<String>,
+    /// `available` or `soldOut`.
+    pub status: Option<String>,
+}
+
+#[derive(Deserialize, Debug)]
+#[serde(rename_all = "PascalCase")]
+pub struct DescribeRegionsBody {
+    pub request_id: String,
+    #[serde(default, deserialize_with = "unwrap_list")]
+    pub regions: Vec<Region>,
+}
@@ -0,0 +1,824 @@
+//! Typed parameters of the ECS operations accepting many optional parameters.
+//!
+//! The builders encode the lists as Aliyun expects them: repeated parameters are numbered
+//! from 1 (`Tag.1.Key`, `Tag.1.Value`, `Tag.2.Key`...) and the parameters documented as
+//! JSON arrays (e.g. `InstanceIds`) are serialized as such.
+
+use std::collections::BTreeMap;
+
+use base64::{engine::general_purpose, Engine as _};
+
+use crate::client::ecs::instances::InstanceState;
+
+/// The billing method of an instance.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum InstanceChargeType {
+    /// Subscription, paid for `Period` months in advance.
+    PrePaid,
+    /// Pay-as-you-go.
+    PostPaid,
+}
+
+/// The bidding policy of a pay-as-you-go instance.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SpotStrategy {
+    /// A regular instance.
+    NoSpot,
+    /// A spot instance with a maximum hourly price, see `RunInstancesRequest::spot_price_limit`.
+    SpotWithPriceLimit,
+    /// A spot instance paid at the market price.
+    SpotAsPriceGo,
+}
+
+impl InstanceChargeType {
+    /// The value of the `InstanceChargeType` parameter.
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            InstanceChargeType::PrePaid => "PrePaid",
+            InstanceChargeType::PostPaid => "PostPaid",
+        }
+    }
+}
+
+impl std::fmt::Display for InstanceChargeType {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+impl SpotStrategy {
+    /// The value of the `SpotStrategy` parameter.
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            SpotStrategy::NoSpot => "NoSpot",
+            SpotStrategy::SpotWithPriceLimit => "SpotWithPriceLimit",
+            SpotStrategy::SpotAsPriceGo => "SpotAsPriceGo",
+        }
+    }
+}
+
+impl std::fmt::Display for SpotStrategy {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+/// The states `DescribeInstancesRequest::status` filters by. Unlike `InstanceState` it has
+/// no `Unknown` variant, so a filter always reaches Aliyun.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum InstanceStatusFilter {
+    Pending,
+    Running,
+    Starting,
+    Stopping,
+    Stopped,
+}
+
+impl InstanceStatusFilter {
+    /// The value of the `Status` parameter.
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            InstanceStatusFilter::Pending => "Pending",
+            InstanceStatusFilter::Running => "Running",
+            InstanceStatusFilter::Starting => "Starting",
+            InstanceStatusFilter::Stopping => "Stopping",
+            InstanceStatusFilter::Stopped => "Stopped",
+        }
+    }
+}
+
+impl std::fmt::Display for InstanceStatusFilter {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+/// Converts the state of a described instance into a filter; `InstanceState::Unknown`
+/// is not an Aliyun state and is given back as the error.
+impl TryFrom<InstanceState> for InstanceStatusFilter {
+    type Error = InstanceState;
+
+    fn try_from(state: InstanceState) -> Result<Self, Self::Error> {
+        match state {
+            InstanceState::Pending => Ok(InstanceStatusFilter::Pending),
+            InstanceState::Running => Ok(InstanceStatusFilter::Running),
+            InstanceState::Starting => Ok(InstanceStatusFilter::Starting),
+            InstanceState::Stopping => Ok(InstanceStatusFilter::Stopping),
+            InstanceState::Stopped => Ok(InstanceStatusFilter::Stopped),
+            InstanceState::Unknown => Err(state),
+        }
+    }
+}
+
+/// The system disk of a new instance; unset fields use Aliyun's defaults.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct SystemDisk {
+    /// E.g. `cloud_essd`, `cloud_ssd` or `cloud_efficiency`.
+    pub category: Option<String>,
+    /// The size in GiB.
+    pub size: Option<u32>,
+    /// `PL0` to `PL3`, for ESSDs.
+    pub performance_level: Option<String>,
+    pub disk_name: Option<String>,
+}
+
+/// A data disk of a new instance; unset fields use Aliyun's defaults.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct DataDisk {
+    /// E.g. `cloud_essd`, `cloud_ssd` or `cloud_efficiency`.
+    pub category: Option<String>,
+    /// The size in GiB; required unless the disk is created from `snapshot_id`.
+    pub size: Option<u32>,
+    pub snapshot_id: Option<String>,
+    /// `PL0` to `PL3`, for ESSDs.
+    pub performance_level: Option<String>,
+    pub disk_name: Option<String>,
+    pub encrypted: Option<bool>,
+    /// Whether the disk is released with the instance (`true` by default).
+    pub delete_with_instance: Option<bool>,
+}
+
+/// The filters and paging of the `DescribeInstances` operation; every filter is optional.
+///
+/// ```ignore
+/// let request = DescribeInstancesRequest::new()
+///     .vpc_id("vpc-bp1opxu1zkhn00gzv****")
+///     .status(InstanceStatusFilter::Running)
+///     .tag("env", "prod")
+///     .page_size(100);
+/// let page = client.ecs("cn-hangzhou").describe_instances(&request).await?;
+/// ```
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct DescribeInstancesRequest {
+    instance_ids: Vec<String>,
+    vpc_id: Option<String>,
+    vswitch_id: Option<String>,
+    zone_id: Option<String>,
+    status: Option<InstanceStatusFilter>,
+    instance_name: Option<String>,
+    instance_type: Option<String>,
+    instance_type_family: Option<String>,
+    image_id: Option<String>,
+    security_group_id: Option<String>,
+    instance_network_type: Option<String>,
+    instance_charge_type: Option<InstanceChargeType>,
+    resource_group_id: Option<String>,
+    key_pair_name: Option<String>,
+    private_ip_addresses: Vec<String>,
+    public_ip_addresses: Vec<String>,
+    eip_addresses: Vec<String>,
+    tags: Vec<(String, Option<String>)>,
+    page_number: Option<u32>,
+    page_size: Option<u32>,
+    next_token: Option<String>,
+    max_results: Option<u32>,
+}
+
+fn strings<I, S>(values: I) -> impl Iterator<Item = String>
+where
+    I: IntoIterator<Item = S>,
+    S: Into<String>,
+{
+    values.into_iter().map(Into::into)
+}
+
+/// Inserts `values` as a JSON array, unless there are none.
+fn insert_json_array(params: &mut BTreeMap<String, String>, name: &str, values: &[String]) {
+    if !values.is_empty() {
+        params.insert(
+            name.to_string(),
+            serde_json::to_string(values).expect("a list of strings is serializable"),
+        );
+    }
+}
+
+fn insert_optional(
+    params: &mut BTreeMap<String, String>,
+    name: &str,
+    value: Option<impl ToString>,
+) {
+    if let Some(value) = value {
+        params.insert(name.to_string(), value.to_string());
+    }
+}
+
+impl DescribeInstancesRequest {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Only describes the given instances (`InstanceIds`, at most 100).
+    pub fn instance_ids<I, S>(mut self, instance_ids: I) -> Self
+    where
+        I: IntoIterator<Item = S>,
+        S: Into<String>,
+    {
+        self.instance_ids.extend(strings(instance_ids));
+        self
+    }
+
+    pub fn vpc_id(mut self, vpc_id: impl Into<String>) -> Self {
+        self.vpc_id = Some(vpc_id.into());
+        self
+    }
+
+    pub fn vswitch_id(mut self, vswitch_id: impl Into<String>) -> Self {
+        self.vswitch_id = Some(vswitch_id.into());
+        self
+    }
+
+    pub fn zone_id(mut self, zone_id: impl Into<String>) -> Self {
+        self.zone_id = Some(zone_id.into());
+        self
+    }
+
+    /// Filters by state.
+    pub fn status(mut self, status: InstanceStatusFilter) -> Self {
+        self.status = Some(status);
+        self
+    }
+
+    /// Filters by name; `*` matches any characters, e.g. `web-*`.
+    pub fn instance_name(mut self, instance_name: impl Into<String>) -> Self {
+        self.instance_name = Some(instance_name.into());
+        self
+    }
+
+    pub fn instance_type(mut self, instance_type: impl Into<String>) -> Self {
+        self.instance_type = Some(instance_type.into());
+        self
+    }
+
+    pub fn instance_type_family(mut self, instance_type_family: impl Into<String>) -> Self {
+        self.instance_type_family = Some(instance_type_family.into());
+        self
+    }
+
+    pub fn image_id(mut self, image_id: impl Into<String>) -> Self {
+        self.image_id = Some(image_id.into());
+        self
+    }
+
+    pub fn security_group_id(mut self, security_group_id: impl Into<String>) -> Self {
+        self.security_group_id = Some(security_group_id.into());
+        self
+    }
+
+    /// `classic` or `vpc`.
+    pub fn instance_network_type(mut self, instance_network_type: impl Into<String>) -> Self {
+        self.instance_network_type = Some(instance_network_type.into());
+        self
+    }
+
+    pub fn instance_charge_type(mut self, instance_charge_type: InstanceChargeType) -> Self {
+        self.instance_charge_type = Some(instance_charge_type);
+        self
+    }
+
+    pub fn resource_group_id(mut self, resource_group_id: impl Into<String>) -> Self {
+        self.resource_group_id = Some(resource_group_id.into());
+        self
+    }
+
+    pub fn key_pair_name(mut self, key_pair_name: impl Into<String>) -> Self {
+        self.key_pair_name = Some(key_pair_name.into());
+        self
+    }
+
+    /// Filters by private IP addresses of VPC instances (`PrivateIpAddresses`).
+    pub fn private_ip_addresses<I, S>(mut self, addresses: I) -> Self
+    where
+        I: IntoIterator<Item = S>,
+        S: Into<String>,
+    {
+        self.private_ip_addresses.extend(strings(addresses));
+        self
+    }
+
+    /// Filters by public IP addresses (`PublicIpAddresses`).
+    pub fn public_ip_addresses<I, S>(mut self, addresses: I) -> Self
+    where
+        I: IntoIterator<Item = S>,
+        S: Into<String>,
+    {
+        self.public_ip_addresses.extend(strings(addresses));
+        self
+    }
+
+    /// Filters by elastic IP addresses (`EipAddresses`).
+    pub fn eip_addresses<I, S>(mut self, addresses: I) -> Self
+    where
+        I: IntoIterator<Item = S>,
+        S: Into<String>,
+    {
+        self.eip_addresses.extend(strings(addresses));
+        self
+    }
+
+    /// Only describes the instances with the tag `key` set to `value` (at most 20 tags).
+    pub fn tag(mut self, key: impl Into<String>, value: impl Into<String>) -> Self {
+        self.tags.push((key.into(), Some(value.into())));
+        self
+    }
+
+    /// Only describes the instances with the tag `key`, whatever its value.
+    pub fn tag_key(mut self, key: impl Into<String>) -> Self {
+        self.tags.push((key.into(), None));
+        self
+    }
+
+    /// The 1-based page to return, with `page_size`.
+    pub fn page_number(mut self, page_number: u32) -> Self {
+        self.page_number = Some(page_number);
+        self
+    }
+
+    pub fn page_size(mut self, page_size: u32) -> Self {
+        self.page_size = Some(page_size);
+        self
+    }
+
+    /// The token of the page to return, from the `NextToken` of the previous page, with
+    /// `max_results`.
+    pub fn next_token(mut self, next_token: impl Into<String>) -> Self {
+        self.next_token = Some(next_token.into());
+        self
+    }
+
+    pub fn max_results(mut self, max_results: u32) -> Self {
+        self.max_results = Some(max_results);
+        self
+    }
+
+    /// Inserts the parameters of the request into `params`.
+    pub(crate) fn insert_params(&self, params: &mut BTreeMap<String, String>) {
+        insert_json_array(params, "InstanceIds", &self.instance_ids);
+        insert_optional(params, "VpcId", self.vpc_id.as_ref());
+        insert_optional(params, "VSwitchId", self.vswitch_id.as_ref());
+        insert_optional(params, "ZoneId", self.zone_id.as_ref());
+        insert_optional(params, "Status", self.status.map(|status| status.as_str()));
+        insert_optional(params, "InstanceName", self.instance_name.as_ref());
+        insert_optional(params, "InstanceType", self.instance_type.as_ref());
+        insert_optional(
+            params,
+            "InstanceTypeFamily",
+            self.instance_type_family.as_ref(),
+        );
+        insert_optional(params, "ImageId", self.image_id.as_ref());
+        insert_optional(params, "SecurityGroupId", self.security_group_id.as_ref());
+        insert_optional(
+            params,
+            "InstanceNetworkType",
+            self.instance_network_type.as_ref(),
+        );
+        insert_optional(
+            params,
+            "InstanceChargeType",
+            self.instance_charge_type
+                .map(|charge_type| charge_type.as_str()),
+        );
+        insert_optional(params, "ResourceGroupId", self.resource_group_id.as_ref());
+        insert_optional(params, "KeyPairName", self.key_pair_name.as_ref());
+        insert_json_array(params, "PrivateIpAddresses", &self.private_ip_addresses);
+        insert_json_array(params, "PublicIpAddresses", &self.public_ip_addresses);
+        insert_json_array(params, "EipAddresses", &self.eip_addresses);
+        for (i, (key, value)) in self.tags.iter().enumerate() {
+            params.insert(format!("Tag.{}.Key", i + 1), key.clone());
+            insert_optional(params, &format!("Tag.{}.Value", i + 1), value.as_ref());
+        }
+        insert_optional(params, "PageNumber", self.page_number);
+        insert_optional(params, "PageSize", self.page_size);
+        insert_optional(params, "NextToken", self.next_token.as_ref());
+        insert_optional(params, "MaxResults", self.max_results);
+    }
+}
+
+/// The parameters of the `RunInstances` operation, creating and starting instances.
+///
+/// The `ClientToken` making retries idempotent is generated when none is set.
+///
+/// ```ignore
+/// let request = RunInstancesRequest::new("aliyun_3_x64_20G_alibase_20240819.vhd", "ecs.g7.large")
+///     .vswitch_id("vsw-bp1s5fnvk4gn2tws0****")
+///     .security_group_id("sg-bp15ed6xe1yxeycg****")
+///     .system_disk(SystemDisk {
+///         category: Some("cloud_essd".to_string()),
+///         size: Some(40),
+///         ..Default::default()
+///     })
+///     .key_pair_name("deploy")
+///     .user_data("#!/bin/sh\necho hello")
+///     .tag("env", "prod")
+///     .amount(2);
+/// let body = client.ecs("cn-hangzhou").run_instances(&request).await?;
+/// ```
+#[derive(Debug, Clone, PartialEq)]
+pub struct RunInstancesRequest {
+    image_id: String,
+    instance_type: String,
+    zone_id: Option<String>,
+    vswitch_id: Option<String>,
+    security_group_ids: Vec<String>,
+    instance_name: Option<String>,
+    host_name: Option<String>,
+    description: Option<String>,
+    system_disk: Option<SystemDisk>,
+    data_disks: Vec<DataDisk>,
+    instance_charge_type: Option<InstanceChargeType>,
+    period: Option<u32>,
+    spot_strategy: Option<SpotStrategy>,
+    spot_price_limit: Option<f64>,
+    internet_max_bandwidth_out: Option<u32>,
+    amount: Option<u32>,
+    min_amount: Option<u32>,
+    key_pair_name: Option<String>,
+    user_data: Option<String>,
+    ram_role_name: Option<String>,
+    tags: Vec<(String, String)>,
+    client_token: Option<String>,
+    dry_run: bool,
+}
+
+impl RunInstancesRequest {
+    pub fn new(image_id: impl Into<String>, instance_type: impl Into<String>) -> Self {
+        Self {
+            image_id: image_id.into(),
+            instance_type: instance_type.into(),
+            zone_id: None,
+            vswitch_id: None,
+            security_group_ids: Vec::new(),
+            instance_name: None,
+            host_name: None,
+            description: None,
+            system_disk: None,
+            data_disks: Vec::new(),
+            instance_charge_type: None,
+            period: None,
+            spot_strategy: None,
+            spot_price_limit: None,
+            internet_max_bandwidth_out: None,
+            amount: None,
+            min_amount: None,
+            key_pair_name: None,
+            user_data: None,
+            ram_role_name: None,
+            tags: Vec::new(),
+            client_token: None,
+            dry_run: false,
+        }
+    }
+
+    /// The zone of the instances; implied by `vswitch_id` in a VPC.
+    pub fn zone_id(mut self, zone_id: impl Into<String>) -> Self {
+        self.zone_id = Some(zone_id.into());
+        self
+    }
+
+    /// Creates the instances in a VPC, in the given vSwitch.
+    pub fn vswitch_id(mut self, vswitch_id: impl Into<String>) -> Self {
+        self.vswitch_id = Some(vswitch_id.into());
+        self
+    }
+
+    /// Adds the instances to a security group; may be called several times.
+    pub fn security_group_id(mut self, security_group_id: impl Into<String>) -> Self {
+        self.security_group_ids.push(security_group_id.into());
+        self
+    }
+
+    /// Adds the instances to several security groups.
+    pub fn security_group_ids<I, S>(mut self, security_group_ids: I) -> Self
+    where
+        I: IntoIterator<Item = S>,
+        S: Into<String>,
+    {
+        self.security_group_ids.extend(strings(security_group_ids));
+        self
+    }
+
+    pub fn instance_name(mut self, instance_name: impl Into<String>) -> Self {
+        self.instance_name = Some(instance_name.into());
+        self
+    }
+
+    pub fn host_name(mut self, host_name: impl Into<String>) -> Self {
+        self.host_name = Some(host_name.into());
+        self
+    }
+
+    pub fn description(mut self, description: impl Into<String>) -> Self {
+        self.description = Some(description.into());
+        self
+    }
+
+    pub fn system_disk(mut self, system_disk: SystemDisk) -> Self {
+        self.system_disk = Some(system_disk);
+        self
+    }
+
+    /// Attaches a data disk; may be called several times (at most 16 disks).
+    pub fn data_disk(mut self, data_disk: DataDisk) -> Self {
+        self.data_disks.push(data_disk);
+        self
+    }
+
+    pub fn instance_charge_type(mut self, instance_charge_type: InstanceChargeType) -> Self {
+        self.instance_charge_type = Some(instance_charge_type);
+        self
+    }
+
+    /// The subscription duration in months, with `InstanceChargeType::PrePaid`.
+    pub fn period(mut self, months: u32) -> Self {
+        self.period = Some(months);
+        self
+    }
+
+    pub fn spot_strategy(mut self, spot_strategy: SpotStrategy) -> Self {
+        self.spot_strategy = Some(spot_strategy);
+        self
+    }
+
+    /// The maximum hourly price, with `SpotStrategy::SpotWithPriceLimit`.
+    pub fn spot_price_limit(mut self, spot_price_limit: f64) -> Self {
+        self.spot_price_limit = Some(spot_price_limit);
+        self
+    }
+
+    /// The public bandwidth in Mbit/s; a public IP address is assigned when it is positive.
+    pub fn internet_max_bandwidth_out(mut self, mbps: u32) -> Self {
+        self.internet_max_bandwidth_out = Some(mbps);
+        self
+    }
+
+    /// The number of instances to create (1 by default).
+    pub fn amount(mut self, amount: u32) -> Self {
+        self.amount = Some(amount);
+        self
+    }
+
+    /// The minimum number of instances to create when the stock does not allow `amount`.
+    pub fn min_amount(mut self, min_amount: u32) -> Self {
+        self.min_amount = Some(min_amount);
+        self
+    }
+
+    pub fn key_pair_name(mut self, key_pair_name: impl Into<String>) -> Self {
+        self.key_pair_name = Some(key_pair_name.into());
+        self
+    }
+
+    /// The user data run at the first boot, e.g. a shell script; it is base64-encoded.
+    pub fn user_data(mut self, user_data: impl AsRef<[u8]>) -> Self {
+        self.user_data = Some(general_purpose::STANDARD.encode(user_data));
+        self
+    }
+
+    /// The RAM role attached to the instances.
+    pub fn ram_role_name(mut self, ram_role_name: impl Into<String>) -> Self {
+        self.ram_role_name = Some(ram_role_name.into());
+        self
+    }
+
+    /// Tags the instances; may be called several times (at most 20 tags).
+    pub fn tag(mut self, key: impl Into<String>, value: impl Into<String>) -> Self {
+        self.tags.push((key.into(), value.into()));
+        self
+    }
+
+    /// The token making retries of the request idempotent, at most 64 ASCII characters.
+    pub fn client_token(mut self, client_token: impl Into<String>) -> Self {
+        self.client_token = Some(client_token.into());
+        self
+    }
+
+    /// Only checks the request (permissions, parameters, stock) without creating anything.
+    pub fn dry_run(mut self, dry_run: bool) -> Self {
+        self.dry_run = dry_run;
+        self
+    }
+
+    /// Inserts the parameters of the request into `params`.
+    pub(crate) fn insert_params(&self, params: &mut BTreeMap<String, String>) {
+        params.insert("ImageId".to_string(), self.image_id.clone());
+        params.insert("InstanceType".to_string(), self.instance_type.clone());
+        insert_optional(params, "ZoneId", self.zone_id.as_ref());
+        insert_optional(params, "VSwitchId", self.vswitch_id.as_ref());
+        match self.security_group_ids.as_slice() {
+            [] => {}
+            [security_group_id] => {
+                params.insert("SecurityGroupId".to_string(), security_group_id.clone());
+            }
+            security_group_ids => {
+                for (i, security_group_id) in security_group_ids.iter().enumerate() {
+                    params.insert(
+                        format!("SecurityGroupIds.{}", i + 1),
+                        security_group_id.clone(),
+                    );
+                }
+            }
+        }
+        insert_optional(params, "InstanceName", self.instance_name.as_ref());
+        insert_optional(params, "HostName", self.host_name.as_ref());
+        insert_optional(params, "Description", self.description.as_ref());
+        if let Some(disk) = &self.system_disk {
+            insert_optional(params, "SystemDisk.Category", disk.category.as_ref());
+            insert_optional(params, "SystemDisk.Size", disk.size);
+            insert_optional(
+                params,
+                "SystemDisk.PerformanceLevel",
+                disk.performance_level.as_ref(),
+            );
+            insert_optional(params, "SystemDisk.DiskName", disk.disk_name.as_ref());
+        }
+        for (i, disk) in self.data_disks.iter().enumerate() {
+            let prefix = format!("DataDisk.{}", i + 1);
+            insert_optional(
+                params,
+                &format!("{}.Category", prefix),
+                disk.category.as_ref(),
+            );
+            insert_optional(params, &format!("{}.Size", prefix), disk.size);
+            insert_optional(
+                params,
+                &format!("{}.SnapshotId", prefix),
+                disk.snapshot_id.as_ref(),
+            );
+            insert_optional(
+                params,
+                &format!("{}.PerformanceLevel", prefix),
+                disk.performance_level.as_ref(),
+            );
+            insert_optional(
+                params,
+                &format!("{}.DiskName", prefix),
+                disk.disk_name.as_ref(),
+            );
+            insert_optional(params, &format!("{}.Encrypted", prefix), disk.encrypted);
+            insert_optional(
+                params,
+                &format!("{}.DeleteWithInstance", prefix),
+                disk.delete_with_instance,
+            );
+        }
+        insert_optional(
+            params,
+            "InstanceChargeType",
+            self.instance_charge_type
+                .map(|charge_type| charge_type.as_str()),
+        );
+        if let Some(period) = self.period {
+            params.insert("Period".to_string(), period.to_string());
+            params.insert("PeriodUnit".to_string(), "Month".to_string());
+        }
+        insert_optional(
+            params,
+            "SpotStrategy",
+            self.spot_strategy.map(|strategy| strategy.as_str()),
+        );
+        insert_optional(params, "SpotPriceLimit", self.spot_price_limit);
+        insert_optional(
+            params,
+            "InternetMaxBandwidthOut",
+            self.internet_max_bandwidth_out,
+        );
+        insert_optional(params, "Amount", self.amount);
+        insert_optional(params, "MinAmount", self.min_amount);
+        insert_optional(params, "KeyPairName", self.key_pair_name.as_ref());
+        insert_optional(params, "UserData", self.user_data.as_ref());
+        insert_optional(params, "RamRoleName", self.ram_role_name.as_ref());
+        for (i, (key, value)) in self.tags.iter().enumerate() {
+            params.insert(format!("Tag.{}.Key", i + 1), key.clone());
+            params.insert(format!("Tag.{}.Value", i + 1), value.clone());
+        }
+        insert_optional(params, "ClientToken", self.client_token.as_ref());
+        if self.dry_run {
+            params.insert("DryRun".to_string(), "true".to_string());
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_describe_instances_params() {
+        let request = DescribeInstancesRequest::new()
+            .instance_ids(["i-1", "i-2"])
+            .vpc_id("vpc-1")
+            .status(InstanceStatusFilter::Running)
+            .private_ip_addresses(["10.0.0.1"])
+            .tag("env", "prod")
+            .tag_key("team")
+            .page_size(100);
+        let mut params = BTreeMap::new();
+        request.insert_params(&mut params);
+
+        let expected = BTreeMap::from(
+            [
+                ("InstanceIds", r#"["i-1","i-2"]"#),
+                ("VpcId", "vpc-1"),
+                ("Status", "Running"),
+                ("PrivateIpAddresses", r#"["10.0.0.1"]"#),
+                ("Tag.1.Key", "env"),
+                ("Tag.1.Value", "prod"),
+                ("Tag.2.Key", "team"),
+                ("PageSize", "100"),
+            ]
+            .map(|(k, v)| (k.to_string(), v.to_string())),
+        );
+        assert_eq!(params, expected);
+    }
+
+    #[test]
+    fn test_unknown_state_is_not_a_filter() {
+        assert_eq!(
+            InstanceStatusFilter::try_from(InstanceState::Unknown),
+            Err(InstanceState::Unknown)
+        );
+        let filter = InstanceStatusFilter::try_from(InstanceState::Stopped).unwrap();
+        assert_eq!(filter, InstanceStatusFilter::Stopped);
+
+        let mut params = BTreeMap::new();
+        DescribeInstancesRequest::new()
+            .status(filter)
+            .insert_params(&mut params);
+        assert_eq!(params["Status"], "Stopped");
+    }
+
+    #[test]
+    fn test_wire_names() {
+        assert_eq!(InstanceState::Stopped.to_string(), "Stopped");
+        assert_eq!(InstanceChargeType::PostPaid.as_str(), "PostPaid");
+        assert_eq!(SpotStrategy::SpotAsPriceGo.as_str(), "SpotAsPriceGo");
+        assert_eq!(
+            serde_json::to_value(InstanceState::Starting).unwrap(),
+            InstanceState::Starting.as_str()
+        );
+    }
+
+    #[test]
+    fn test_empty_request_has_no_params() {
+        let mut params = BTreeMap::new();
+        DescribeInstancesRequest::new().insert_params(&mut params);
+        assert!(params.is_empty());
+    }
+
+    #[test]
+    fn test_run_instances_params() {
+        let request = RunInstancesRequest::new("img-1", "ecs.g7.large")
+            .vswitch_id("vsw-1")
+            .security_group_ids(["sg-1", "sg-2"])
+            .system_disk(SystemDisk {
+                category: Some("cloud_essd".to_string()),
+                size: Some(40),
+                ..Default::default()
+            })
+            .data_disk(DataDisk {
+                size: Some(100),
+                delete_with_instance: Some(false),
+                ..Default::default()
+            })
+            .instance_charge_type(InstanceChargeType::PostPaid)
+            .spot_strategy(SpotStrategy::SpotAsPriceGo)
+            .amount(2)
+            .user_data("#!/bin/sh")
+            .tag("env", "prod")
+            .dry_run(true);
+        let mut params = BTreeMap::new();
+        request.insert_params(&mut params);
+
+        let expected = BTreeMap::from(
+            [
+                ("ImageId", "img-1"),
+                ("InstanceType", "ecs.g7.large"),
+                ("VSwitchId", "vsw-1"),
+                ("SecurityGroupIds.1", "sg-1"),
+                ("SecurityGroupIds.2", "sg-2"),
+                ("SystemDisk.Category", "cloud_essd"),
+                ("SystemDisk.Size", "40"),
+                ("DataDisk.1.Size", "100"),
+                ("DataDisk.1.DeleteWithInstance", "false"),
+                ("InstanceChargeType", "PostPaid"),
+                ("SpotStrategy", "SpotAsPriceGo"),
+                ("Amount", "2"),
+                ("UserData", "IyEvYmluL3No"),
+                ("Tag.1.Key", "env"),
+                ("Tag.1.Value", "prod"),
+                ("DryRun", "true"),
+            ]
+            .map(|(k, v)| (k.to_string(), v.to_string())),
+        );
+        assert_eq!(params, expected);
+    }
+
+    #[test]
+    fn test_single_security_group() {
+        let mut params = BTreeMap::new();
+        RunInstancesRequest::new("img-1", "ecs.g7.large")
+            .security_group_id("sg-1")
+            .insert_params(&mut params);
+        assert_eq!(params["SecurityGroupId"], "sg-1");
+        assert!(!params.contains_key("SecurityGroupIds.1"));
+    }
+}
@@ -0,0 +1,417 @@
+//! Waiters polling `DescribeInstanceStatus` until instances reach a state, used after
+//! `start_instances`, `stop_instances`, `reboot_instance`, `run_instances` or
+//! `delete_instance`.
+
+use std::{collections::BTreeMap, time::Duration};
+
+use tokio::time::Instant;
+
+use crate::client::{
+    ecs::{
+        instances::{InstanceState, InstanceStatus},
+        EcsClient,
+    },
+    error::AdvancedClientError,
+};
+
+/// The default time to wait before giving up.
+pub const DEFAULT_WAITER_TIMEOUT: Duration = Duration::from_secs(600);
+/// The default delay before the second poll, doubled after every poll.
+pub const DEFAULT_WAITER_INTERVAL: Duration = Duration::from_secs(2);
+/// The default upper bound of the delay between two polls.
+pub const DEFAULT_WAITER_MAX_INTERVAL: Duration = Duration::from_secs(20);
+/// The largest number of instance IDs queried by one `DescribeInstanceStatus` request.
+const MAX_IDS_PER_POLL: usize = 100;
+
+/// How long and how often a waiter polls.
+///
+/// A `Duration` converts into the configuration waiting at most that long with the default
+/// polling intervals:
+///
+/// ```ignore
+/// ecs.wait_until_instances_running(&["i-1"], Duration::from_secs(300)).await?;
+/// let config = WaiterConfig::new(Duration::from_secs(300))
+///     .with_backoff(Duration::from_secs(1), Duration::from_secs(5));
+/// ecs.wait_until_instances_running(&["i-1"], config).await?;
+/// ```
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct WaiterConfig {
+    timeout: Duration,
+    interval: Duration,
+    max_interval: Duration,
+}
+
+impl Default for WaiterConfig {
+    fn default() -> Self {
+        Self::new(DEFAULT_WAITER_TIMEOUT)
+    }
+}
+
+impl From<Duration> for WaiterConfig {
+    fn from(timeout: Duration) -> Self {
+        Self::new(timeout)
+    }
+}
+
+impl WaiterConfig {
+    /// Waits at most `timeout`, polling with the default intervals.
+    pub fn new(timeout: Duration) -> Self {
+        Self {
+            timeout,
+            interval: DEFAULT_WAITER_INTERVAL,
+            max_interval: DEFAULT_WAITER_MAX_INTERVAL,
+        }
+    }
+
+    /// Waits `interval` before the second poll, doubling the delay after every poll up to
+    /// `max_interval`. Use the same value twice to poll at a fixed rate.
+    pub fn with_backoff(mut self, interval: Duration, max_interval: Duration) -> Self {
+        self.interval = interval;
+        self.max_interval = max_interval.max(interval);
+        self
+    }
+
+    pub fn timeout(&self) -> Duration {
+        self.timeout
+    }
+
+    /// The delay after the `poll`-th poll.
+    fn delay(&self, poll: u32) -> Duration {
+        self.interval
+            .saturating_mul(2u32.saturating_pow(poll.saturating_sub(1)))
+            .min(self.max_interval)
+    }
+}
+
+/// What the instances are awaited for.
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+enum Target {
+    State(InstanceState),
+    Deleted,
+}
+
+impl Target {
+    fn description(&self, instance_ids: &[&str]) -> String {
+        let state = match self {
+            Target::State(state) => state.to_string(),
+            Target::Deleted => "deleted".to_string(),
+        };
+        format!("instances {} to be {}", instance_ids.join(", "), state)
+    }
+}
+
+/// Whether an instance observed in `seen` states then in `state` went back to the stable
+/// state it was leaving, e.g. `Starting` then `Stopped` when the start failed.
+fn has_failed(target: InstanceState, seen: &[InstanceState], state: InstanceState) -> bool {
+    let (transitional, opposite) = match target {
+        InstanceState::Running => (
+            &[InstanceState::Pending, InstanceState::Starting][..],
+            InstanceState::Stopped,
+        ),
+        InstanceState::Stopped => (&[InstanceState::Stopping][..], InstanceState::Running),
+        _ => return false,
+    };
+    state == opposite && seen.iter().any(|s| transitional.contains(s))
+}
+
+impl EcsClient {
+    /// Wait until every instance of `instance_ids` is `Running` and return their statuses.
+    ///
+    /// Instances not listed yet, e.g. right after `run_instances`, are polled again. Fails
+    /// early with `AdvancedClientError::WaiterFailure` when a listed instance is released
+    /// or falls back to `Stopped` after `Starting`, and with
+    /// `AdvancedClientError::WaiterTimeout` when the deadline elapses.
+    pub async fn wait_until_instances_running(
+        &self,
+        instance_ids: &[&str],
+        config: impl Into<WaiterConfig>,
+    ) -> Result<Vec<InstanceStatus>, AdvancedClientError> {
+        self.wait_until(
+            instance_ids,
+            Target::State(InstanceState::Running),
+            config.into(),
+        )
+        .await
+    }
+
+    /// Wait until every instance of `instance_ids` is `Stopped` and return their statuses.
+    ///
+    /// Fails early when a listed instance is released or goes back to `Running` after
+    /// `Stopping`.
+    pub async fn wait_until_instances_stopped(
+        &self,
+        instance_ids: &[&str],
+        config: impl Into<WaiterConfig>,
+    ) -> Result<Vec<InstanceStatus>, AdvancedClientError> {
+        self.wait_until(
+            instance_ids,
+            Target::State(InstanceState::Stopped),
+            config.into(),
+        )
+        .await
+    }
+
+    /// Wait until `instance_id` is no longer listed by `DescribeInstanceStatus`.
+    pub async fn wait_until_instance_deleted(
+        &self,
+        instance_id: &str,
+        config: impl Into<WaiterConfig>,
+    ) -> Result<(), AdvancedClientError> {
+        self.wait_until(&[instance_id], Target::Deleted, config.into())
+            .await
+            .map(|_| ())
+    }
+
+    async fn wait_until(
+        &self,
+        instance_ids: &[&str],
+        target: Target,
+        config: WaiterConfig,
+    ) -> Result<Vec<InstanceStatus>, AdvancedClientError> {
+        let started = Instant::now();
+        let deadline = started + config.timeout;
+        let mut seen: BTreeMap<&str, Vec<InstanceState>> = BTreeMap::new();
+        let mut poll = 0;
+        loop {
+            poll += 1;
+            let describe = async {
+                let mut statuses: Vec<InstanceStatus> = Vec::new();
+                for ids in instance_ids.chunks(MAX_IDS_PER_POLL) {
+                    let body = self.describe_instance_status_by_ids(ids).await?;
+                    statuses.extend(
+                        body.instance_statuses
+                            .into_iter()
+                            .filter(|status| ids.contains(&status.instance_id.as_str())),
+                    );
+                }
+                Ok::<_, AdvancedClientError>(statuses)
+            };
+            // A slow poll, retries included, must not outlive the deadline.
+            let remaining = deadline.saturating_duration_since(Instant::now());
+            let statuses = match tokio::time::timeout(remaining, describe).await {
+                Ok(statuses) => statuses?,
+                Err(_) => {
+                    return Err(AdvancedClientError::WaiterTimeout {
+                        waiting_for: target.description(instance_ids),
+                        elapsed: started.elapsed(),
+                    })
+                }
+            };
+
+            let done = match target {
+                Target::Deleted => statuses.is_empty(),
+                Target::State(state) => {
+                    for instance_id in instance_ids {
+                        let status = statuses
+                            .iter()
+                            .find(|status| status.instance_id == *instance_id);
+                        let failed_state = match status {
+                            // Not listed yet, or released after it was listed.
+                            None => seen
+                                .contains_key(instance_id)
+                                .then(|| "Deleted".to_string()),
+                            Some(status) => {
+                                let seen = seen.entry(instance_id).or_default();
+                                let failed = has_failed(state, seen, status.status);
+                                seen.push(status.status);
+                                failed.then(|| status.status.to_string())
+                            }
+                        };
+                        if let Some(failed_state) = failed_state {
+                            return Err(AdvancedClientError::WaiterFailure {
+                                resource: format!("Instance {}", instance_id),
+                                state: failed_state,
+                                waiting_for: target.description(instance_ids),
+                            });
+                        }
+                    }
+                    statuses.len() == instance_ids.len()
+                        && statuses.iter().all(|status| status.status == state)
+                }
+            };
+            if done {
+                return Ok(statuses);
+            }
+
+            let now = Instant::now();
+            if now >= deadline {
+                return Err(AdvancedClientError::WaiterTimeout {
+                    waiting_for: target.description(instance_ids),
+                    elapsed: now - started,
+                });
+            }
+            tokio::time::sleep(config.delay(poll).min(deadline - now)).await;
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+    use std::sync::{
+        atomic::{AtomicUsize, Ordering},
+        Arc,
+    };
+
+    use crate::client::transport::{HttpRequest, HttpResponse, HttpTransport};
+    use crate::client::AliyunClient;
+    use crate::test_utils::http_stub::{StubRequest, StubServer};
+
+    /// Serves the `i`-th list of (instance id, status) on the `i`-th poll, then the last one.
+    async fn status_server(polls: Vec<Vec<(&'static str, &'static str)>>) -> StubServer {
+        let count = Arc::new(AtomicUsize::new(0));
+        StubServer::start(move |_: &StubRequest| {
+            let poll = count.fetch_add(1, Ordering::SeqCst).min(polls.len() - 1);
+            let statuses: Vec<_> = polls[poll]
+                .iter()
+                .map(|(id, status)| json!({"InstanceId": id, "Status": status}))
+                .collect();
+            let body = json!({
+                "RequestId": "REQ",
+                "TotalCount": statuses.len(),
+                "InstanceStatuses": {"InstanceStatus": statuses}
+            });
+            (200, body.to_string())
+        })
+        .await
+    }
+
+    fn client(server: &StubServer) -> AliyunClient {
+        AliyunClient::builder()
+            .credentials("id".to_string(), "secret".to_string())
+            .endpoint_override("ecs", server.url())
+            .build()
+            .unwrap()
+    }
+
+    fn fast(timeout_ms: u64) -> WaiterConfig {
+        WaiterConfig::new(Duration::from_millis(timeout_ms))
+            .with_backoff(Duration::from_millis(5), Duration::from_millis(10))
+    }
+
+    #[test]
+    fn test_delay() {
+        let config = WaiterConfig::default();
+        assert_eq!(config.delay(1), Duration::from_secs(2));
+        assert_eq!(config.delay(3), Duration::from_secs(8));
+        assert_eq!(config.delay(10), DEFAULT_WAITER_MAX_INTERVAL);
+    }
+
+    #[tokio::test]
+    async fn test_wait_until_instances_running() {
+        let server = status_server(vec![
+            vec![("i-1", "Starting"), ("i-2", "Stopped"), ("i-3", "Stopped")],
+            vec![("i-1", "Running"), ("i-2", "Starting"), ("i-3", "Stopped")],
+            vec![("i-1", "Running"), ("i-2", "Running"), ("i-3", "Stopped")],
+        ])
+        .await;
+        let statuses = client(&server)
+            .ecs("cn-hangzhou")
+            .wait_until_instances_running(&["i-1", "i-2"], fast(5_000))
+            .await
+            .unwrap();
+        assert_eq!(statuses.len(), 2);
+        assert_eq!(server.requests().len(), 3);
+    }
+
+    #[tokio::test]
+    async fn test_failed_start_fails_early() {
+        let server = status_server(vec![vec![("i-1", "Starting")], vec![("i-1", "Stopped")]]).await;
+        let result = client(&server)
+            .ecs("cn-hangzhou")
+            .wait_until_instances_running(&["i-1"], fast(5_000))
+            .await;
+        assert!(matches!(
+            result,
+            Err(AdvancedClientError::WaiterFailure { ref state, .. }) if state == "Stopped"
+        ));
+
+        let server = status_server(vec![vec![("i-2", "Stopping")], vec![]]).await;
+        let result = client(&server)
+            .ecs("cn-hangzhou")
+            .wait_until_instances_stopped(&["i-2"], fast(5_000))
+            .await;
+        assert!(matches!(
+            result,
+            Err(AdvancedClientError::WaiterFailure { ref state, .. }) if state == "Deleted"
+        ));
+    }
+
+    #[tokio::test]
+    async fn test_new_instances_are_awaited_until_listed() {
+        let server = status_server(vec![
+            vec![],
+            vec![("i-1", "Pending")],
+            vec![("i-1", "Running")],
+        ])
+        .await;
+        let statuses = client(&server)
+            .ecs("cn-hangzhou")
+            .wait_until_instances_running(&["i-1"], fast(5_000))
+            .await
+            .unwrap();
+        assert_eq!(statuses[0].instance_id, "i-1");
+
+        // Every poll is a single request for the awaited instances.
+        let requests = server.requests();
+        assert_eq!(requests.len(), 3);
+        assert!(requests
+            .iter()
+            .all(|request| request.path.contains("&InstanceId.1=i-1&")));
+    }
+
+    #[tokio::test]
+    async fn test_wait_until_instance_deleted() {
+        let server = status_server(vec![vec![("i-1", "Stopped")], vec![]]).await;
+        client(&server)
+            .ecs("cn-hangzhou")
+            .wait_until_instance_deleted("i-1", fast(5_000))
+            .await
+            .unwrap();
+        assert_eq!(server.requests().len(), 2);
+    }
+
+    #[tokio::test]
+    async fn test_timeout() {
+        let server = status_server(vec![vec![("i-1", "Stopping")]]).await;
+        let result = client(&server)
+            .ecs("cn-hangzhou")
+            .wait_until_instances_stopped(&["i-1"], fast(50))
+            .await;
+        assert!(matches!(
+            result,
+            Err(AdvancedClientError::WaiterTimeout { .. })
+        ));
+    }
+
+    /// Answers after a minute, like a poll stuck in its retries.
+    struct SlowTransport;
+
+    #[async_trait::async_trait]
+    impl HttpTransport for SlowTransport {
+        async fn send(&self, _: HttpRequest) -> Result<HttpResponse, AdvancedClientError> {
+            tokio::time::sleep(Duration::from_secs(60)).await;
+            Ok(HttpResponse::new(200, "{}".to_string()))
+        }
+    }
+
+    #[tokio::test]
+    async fn test_slow_poll_times_out() {
+        let client = AliyunClient::builder()
+            .credentials("id".to_string(), "secret".to_string())
+            .transport(SlowTransport)
+            .build()
+            .unwrap();
+        let started = Instant::now();
+        let result = client
+            .ecs("cn-hangzhou")
+            .wait_until_instances_running(&["i-1"], fast(50))
+            .await;
+        assert!(matches!(
+            result,
+            Err(AdvancedClientError::WaiterTimeout { .. })
+        ));
+        assert!(started.elapsed() < Duration::from_secs(5));
+    }
+}
@@ -0,0 +1,26 @@
+use serde::Deserialize;
+
+use crate::client::utils::unwrap_list;
+
+#[derive(Deserialize, Debug, Clone, PartialEq)]
+#[serde(rename_all = "PascalCase")]
+pub struct Zone {
+    pub zone_id: String,
+    pub local_name: Option<String>,
+    pub zone_type: Option<String>,
+    /// The resource types that can be created in the zone, e.g. `Instance` or `Disk`.
+    #[serde(default, deserialize_with = "unwrap_list")]
+    pub available_resource_creation: Vec<String>,
+    #[serde(default, deserialize_with = "unwrap_list")]
+    pub available_instance_types: Vec<String>,
+    #[serde(default, deserialize_with = "unwrap_list")]
+    pub available_disk_categories: Vec<String>,
+}
+
+#[derive(Deserialize, Debug)]
+#[serde(rename_all = "PascalCase")]
+pub struct DescribeZonesBody {
+    pub request_id: String,
+    #[serde(default, deserialize_with = "unwrap_list")]
+    pub zones: Vec<Zone>,
+}
@@ -0,0 +1,279 @@
+use std::collections::BTreeMap;
+
+/// The network through which the endpoints are reached.
+#[derive(Clone, Copy, PartialEq, Eq, Default, Debug)]
+pub enum EndpointType {
+    /// The public endpoints, e.g. `ecs.cn-hangzhou.aliyuncs.com`.
+    #[default]
+    Public,
+    /// The endpoints reachable from within a VPC, e.g. `ecs-vpc.cn-hangzhou.aliyuncs.com`.
+    Vpc,
+}
+
+/// Resolves the endpoint of a product, e.g. "ecs", in a region.
+///
+/// An endpoint is a host name, optionally prefixed with a scheme (`http://127.0.0.1:8080`);
+/// HTTPS is used when the scheme is omitted.
+pub trait EndpointResolver: Send + Sync {
+    /// Returns the endpoint of `product` in `region_id`, or its global endpoint when no
+    /// region is given.
+    fn resolve_endpoint(&self, product: &str, region_id: Option<&str>) -> String;
+}
+
+/// The products served by one endpoint per site instead of one per region, with their
+/// China site and international site endpoints.
+const SITE_ENDPOINTS: &[(&str, &str, &str)] = &[(
+    "business",
+    "business.aliyuncs.com",
+    "business.ap-southeast-1.aliyuncs.com",
+)];
+
+/// The regional endpoints whose public host does not follow the naming scheme.
+const REGION_ENDPOINTS: &[(&str, &str, &str)] = &[
+    ("ecs", "cn-hangzhou-finance", "ecs.aliyuncs.com"),
+    ("ecs", "cn-shanghai-finance-1", "ecs.aliyuncs.com"),
+    ("ecs", "cn-shenzhen-finance-1", "ecs.aliyuncs.com"),
+    ("ecs", "cn-north-2-gov-1", "ecs.aliyuncs.com"),
+];
+
+/// The products with regional endpoints in the listed regions only; the requests for the
+/// other regions go to the global endpoint.
+const PARTIALLY_REGIONAL_PRODUCTS: &[(&str, &[&str])] = &[(
+    "sts",
+    &[
+        "cn-hangzhou",
+        "cn-shanghai",
+        "cn-beijing",
+        "cn-shenzhen",
+        "cn-hongkong",
+        "ap-southeast-1",
+        "us-west-1",
+        "eu-central-1",
+    ],
+)];
+
+/// Resolves the endpoints from the built-in tables and the naming scheme of Aliyun:
+/// `{product}.{region}.aliyuncs.com`, `{product}-vpc.{region}.aliyuncs.com` and
+/// `{product}.aliyuncs.com` for the global endpoint.
+///
+/// Products with one endpoint per site (e.g. "business") use their international site
+/// endpoint outside of mainland China. STS has regional endpoints in some regions only
+/// and is reached through `sts.aliyuncs.com` elsewhere, and the ECS requests of the
+/// finance and gov cloud regions (e.g. `cn-shanghai-finance-1`, `cn-north-2-gov-1`) go
+/// to the central ECS endpoint. Other endpoints are set with `with_override`.
+#[derive(Debug, Clone, Default)]
+pub struct DefaultEndpointResolver {
+    endpoint_type: EndpointType,
+    overrides: BTreeMap<(String, Option<String>), String>,
+}
+
+impl DefaultEndpointResolver {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn with_endpoint_type(mut self, endpoint_type: EndpointType) -> Self {
+        self.endpoint_type = endpoint_type;
+        self
+    }
+
+    /// Uses `endpoint` for `product` in `region_id`, or in every region when `region_id`
+    /// is `None`.
+    pub fn with_override(
+        mut self,
+        product: impl Into<String>,
+        region_id: Option<&str>,
+        endpoint: impl Into<String>,
+    ) -> Self {
+        self.overrides.insert(
+            (product.into(), region_id.map(str::to_string)),
+            endpoint.into(),
+        );
+        self
+    }
+
+    fn builtin_endpoint(&self, product: &str, region_id: Option<&str>) -> String {
+        if let Some((_, china, international)) =
+            SITE_ENDPOINTS.iter().find(|(name, _, _)| *name == product)
+        {
+            return match region_id {
+                Some(region) if !is_mainland_china(region) => international.to_string(),
+                _ => china.to_string(),
+            };
+        }
+        if let Some(region) = region_id {
+            if let Some((_, _, endpoint)) = REGION_ENDPOINTS
+                .iter()
+                .find(|(name, id, _)| *name == product && *id == region)
+            {
+                if self.endpoint_type == EndpointType::Public {
+                    return endpoint.to_string();
+                }
+            }
+        }
+        let region_id = region_id.filter(|region| {
+            PARTIALLY_REGIONAL_PRODUCTS
+                .iter()
+                .find(|(name, _)| *name == product)
+                .is_none_or(|(_, regions)| regions.contains(region))
+        });
+        match (region_id, self.endpoint_type) {
+            (None, _) => format!("{}.aliyuncs.com", product),
+            (Some(region), EndpointType::Public) => format!("{}.{}.aliyuncs.com", product, region),
+            (Some(region), EndpointType::Vpc) => {
+                format!("{}-vpc.{}.aliyuncs.com", product, region)
+            }
+        }
+    }
+}
+
+impl EndpointResolver for DefaultEndpointResolver {
+    fn resolve_endpoint(&self, product: &str, region_id: Option<&str>) -> String {
+        let product = product.to_lowercase();
+        let overridden = region_id
+            .and_then(|region| {
+                self.overrides
+                    .get(&(product.clone(), Some(region.to_string())))
+            })
+            .or_else(|| self.overrides.get(&(product.clone(), None)));
+        match overridden {
+            Some(endpoint) => endpoint.clone(),
+            None => self.builtin_endpoint(&product, region_id),
+        }
+    }
+}
+
+fn is_mainland_china(region_id: &str) -> bool {
+    region_id.starts_with("cn-") && region_id != "cn-hongkong"
+}
+
+/// Splits an endpoint into the base URL of the requests and the host.
+pub(crate) fn base_url(endpoint: &str) -> (String, &str) {
+    match endpoint.split_once("://") {
+        Some((_, host)) => (
+            endpoint.trim_end_matches('/').to_string(),
+            host.trim_end_matches('/'),
+        ),
+        None => (format!("https://{}", endpoint), endpoint),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_builtin_endpoints() {
+        let resolver = DefaultEndpointResolver::new();
+        assert_eq!(resolver.resolve_endpoint("ecs", None), "ecs.aliyuncs.com");
+        assert_eq!(
+            resolver.resolve_endpoint("ECS", Some("cn-shanghai")),
+            "ecs.cn-shanghai.aliyuncs.com"
+        );
+        assert_eq!(
+            resolver.resolve_endpoint("business", Some("cn-hangzhou")),
+            "business.aliyuncs.com"
+        );
+        assert_eq!(
+            resolver.resolve_endpoint("business", Some("ap-southeast-1")),
+            "business.ap-southeast-1.aliyuncs.com"
+        );
+
+        let resolver = resolver.with_endpoint_type(EndpointType::Vpc);
+        assert_eq!(
+            resolver.resolve_endpoint("ecs", Some("cn-hangzhou")),
+            "ecs-vpc.cn-hangzhou.aliyuncs.com"
+        );
+    }
+
+    #[test]
+    fn test_region_endpoints() {
+        let resolver = DefaultEndpointResolver::new();
+        for (region, endpoint) in [
+            ("cn-hangzhou-finance", "ecs.aliyuncs.com"),
+            ("cn-shanghai-finance-1", "ecs.aliyuncs.com"),
+            ("cn-shenzhen-finance-1", "ecs.aliyuncs.com"),
+            ("cn-north-2-gov-1", "ecs.aliyuncs.com"),
+        ] {
+            assert_eq!(resolver.resolve_endpoint("ecs", Some(region)), endpoint);
+        }
+        assert_eq!(
+            resolver
+                .clone()
+                .with_endpoint_type(EndpointType::Vpc)
+                .resolve_endpoint("ecs", Some("cn-north-2-gov-1")),
+            "ecs-vpc.cn-north-2-gov-1.aliyuncs.com"
+        );
+    }
+
+    #[test]
+    fn test_sts_endpoints() {
+        let resolver = DefaultEndpointResolver::new();
+        for (region, endpoint) in [
+            ("cn-hangzhou", "sts.cn-hangzhou.aliyuncs.com"),
+            ("cn-shanghai", "sts.cn-shanghai.aliyuncs.com"),
+            ("cn-beijing", "sts.cn-beijing.aliyuncs.com"),
+            ("cn-shenzhen", "sts.cn-shenzhen.aliyuncs.com"),
+            ("cn-hongkong", "sts.cn-hongkong.aliyuncs.com"),
+            ("ap-southeast-1", "sts.ap-southeast-1.aliyuncs.com"),
+            ("us-west-1", "sts.us-west-1.aliyuncs.com"),
+            ("eu-central-1", "sts.eu-central-1.aliyuncs.com"),
+            ("cn-north-2-gov-1", "sts.aliyuncs.com"),
+            ("cn-shanghai-finance-1", "sts.aliyuncs.com"),
+        ] {
+            assert_eq!(resolver.resolve_endpoint("sts", Some(region)), endpoint);
+        }
+        assert_eq!(resolver.resolve_endpoint("sts", None), "sts.aliyuncs.com");
+        assert_eq!(
+            resolver
+                .with_endpoint_type(EndpointType::Vpc)
+                .resolve_endpoint("sts", Some("cn-beijing")),
+            "sts-vpc.cn-beijing.aliyuncs.com"
+        );
+    }
+
+    #[test]
+    fn test_site_endpoints() {
+        let resolver = DefaultEndpointResolver::new();
+        for (region, endpoint) in [
+            (None, "business.aliyuncs.com"),
+            (Some("cn-shanghai"), "business.aliyuncs.com"),
+            (Some("cn-hongkong"), "business.ap-southeast-1.aliyuncs.com"),
+            (Some("eu-central-1"), "business.ap-southeast-1.aliyuncs.com"),
+        ] {
+            assert_eq!(resolver.resolve_endpoint("business", region), endpoint);
+        }
+    }
+
+    #[test]
+    fn test_overrides() {
+        let resolver = DefaultEndpointResolver::new()
+            .with_override("ecs", None, "ecs.example.com")
+            .with_override(
+                "ecs",
+                Some("cn-north-2-gov-1"),
+                "ecs.cn-north-2-gov-1.example.com",
+            );
+        assert_eq!(
+            resolver.resolve_endpoint("ecs", Some("cn-hangzhou")),
+            "ecs.example.com"
+        );
+        assert_eq!(
+            resolver.resolve_endpoint("ecs", Some("cn-north-2-gov-1")),
+            "ecs.cn-north-2-gov-1.example.com"
+        );
+        assert_eq!(resolver.resolve_endpoint("sts", None), "sts.aliyuncs.com");
+    }
+
+    #[test]
+    fn test_base_url() {
+        assert_eq!(
+            base_url("ecs.aliyuncs.com"),
+            ("https://ecs.aliyuncs.com".to_string(), "ecs.aliyuncs.com")
+        );
+        assert_eq!(
+            base_url("http://127.0.0.1:8080/"),
+            ("http://127.0.0.1:8080".to_string(), "127.0.0.1:8080")
+        );
+    }
+}
@@ -1,8 +1,10 @@
-use std::error::Error;
+use std::time::Duration;
 
 use serde::{Deserialize, Serialize};
 use thiserror::Error;
 
+use crate::client::credentials::CredentialsError;
+
 fn default_aliyun_rejection_field() -> String {
     "No content. If you see this please submit a bug issue (to the rust sdk repo).".to_string()
 }
@@ -19,16 +21,84 @@ pub struct AliyunRejection {
     pub recommend: String,
 }
 
+/// The error of every operation of the client. It is `Send + Sync`, so it can be
+/// returned from spawned tasks.
 #[derive(Error, Debug)]
 pub enum AdvancedClientError {
-    #[error("Aliyun rejected the request and returned an error")]
+    #[error("Aliyun rejected the request with {}: {} (request id: {})", .0.code, .0.message, .0.request_id)]
     AliyunRejectError(AliyunRejection),
-    #[error("When using the underlying client to send request it throw an error: {0}")]
-    UnderlyingError(
+    /// The request could not be sent or its response could not be read.
+    #[error("Failed to send the request: {0}")]
+    Transport(#[source] reqwest::Error),
+    /// A timeout configured on the client elapsed.
+    #[error("The request timed out: {0}")]
+    Timeout(#[source] reqwest::Error),
+    /// Aliyun answered with an error status but without an error document.
+    #[error("Aliyun answered with HTTP status {status}: {body}")]
+    HttpStatus { status: u16, body: String },
+    /// The request could not be signed.
+    #[error("Failed to sign the request: {0}")]
+    Signature(String),
+    /// The request could not be built, e.g. its HTTP method is invalid.
+    #[error("Invalid request: {0}")]
+    InvalidRequest(String),
+    #[error("Failed to get the credentials to sign the request: {0}")]
+    Credentials(#[from] CredentialsError),
+    /// A waiter's deadline elapsed before the resources reached the awaited state.
+    #[error("Gave up waiting for {waiting_for} after {elapsed:?}")]
+    WaiterTimeout {
+        waiting_for: String,
+        elapsed: Duration,
+    },
+    /// A resource entered a state from which the awaited state will not be reached.
+    #[error("{resource} entered the {state} state while waiting for {waiting_for}")]
+    WaiterFailure {
+        resource: String,
+        state: String,
+        waiting_for: String,
+    },
+    /// A dry run was answered as a performed request: Aliyun ran the operation, e.g.
+    /// because the `DryRun` parameter was dropped on the way.
+    #[error("The dry run was performed instead of only checked (request id: {request_id})")]
+    DryRunPerformed { request_id: String },
+    #[error("When trying to deserialization the result an error occurred. This should not happened; please using services to debug and open a bug issue: {source}")]
+    ResultDeserializationError {
         #[source]
-        #[from]
-        Box<dyn Error>,
-    ),
-    #[error("When trying to deserialization the result an error occurred. This should not happened; please using services to debug and open a bug issue")]
-    ResultDeserializationError(#[from] serde_json::Error),
+        source: serde_json::Error,
+        /// The raw response body.
+        body: String,
+    },
+}
+
+impl From<reqwest::Error> for AdvancedClientError {
+    fn from(err: reqwest::Error) -> Self {
+        if err.is_timeout() {
+            AdvancedClientError::Timeout(err)
+        } else {
+            AdvancedClientError::Transport(err)
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_error_is_send_and_sync() {
+        fn assert_send_sync<T: Send + Sync + 'static>() {}
+        assert_send_sync::<AdvancedClientError>();
+    }
+
+    #[test]
+    fn test_rejection_display() {
+        let rejection: AliyunRejection = serde_json::from_str(
+            r#"{"RequestId":"REQ","HostId":"ecs.aliyuncs.com","Code":"InvalidAccessKeyId.NotFound","Message":"Specified access key is not found."}"#,
+        )
+        .unwrap();
+        assert_eq!(
+            AdvancedClientError::AliyunRejectError(rejection).to_string(),
+            "Aliyun rejected the request with InvalidAccessKeyId.NotFound: Specified access key is not found. (request id: REQ)"
+        );
+    }
 }
@@ -1,37 +1,234 @@
 pub mod sts;
+pub mod billing;
+pub mod builder;
+pub mod clock;
+pub mod credentials;
+pub mod ecs;
+pub mod endpoint;
 pub mod error;
+pub mod paginator;
+pub mod retry;
+pub mod transport;
 pub(crate) mod utils;
 
-use crate::signing;
-use chrono::Utc;
+use crate::client::builder::AliyunClientBuilder;
+use crate::client::clock::Clock;
+use crate::client::credentials::{
+    Credentials, CredentialsError, CredentialsProvider, DefaultCredentialsChain,
+    StaticCredentialsProvider,
+};
+use crate::client::endpoint::{base_url, EndpointResolver};
+use crate::client::error::{AdvancedClientError, AliyunRejection};
+use crate::client::retry::RetryPolicy;
+use crate::client::transport::{HttpRequest, HttpTransport, ReqwestTransport};
+use crate::signing::{self, SignatureVersion};
+use chrono::{DateTime, TimeDelta, Utc};
 use reqwest;
 use serde_json::Value;
-use std::collections::BTreeMap;
+use std::{collections::BTreeMap, future::Future, sync::Arc};
 use uuid::Uuid;
 
-/// The AliyunClient struct holds credentials and provides methods to send requests.
+/// The provider of `AliyunClient::anonymous`, which has no credentials at all.
+struct AnonymousCredentialsProvider;
+
+#[async_trait::async_trait]
+impl CredentialsProvider for AnonymousCredentialsProvider {
+    async fn provide_credentials(&self) -> Result<Credentials, CredentialsError> {
+        Err(CredentialsError::NotConfigured(
+            "the client is anonymous and can only send anonymous requests".to_string(),
+        ))
+    }
+}
+
+/// A signed request, ready to be sent.
+struct SignedRequest {
+    url: String,
+    headers: Vec<(String, String)>,
+}
+
+/// The AliyunClient struct holds a credentials provider and provides methods to send requests.
+///
+/// All requests share a single pooled HTTP client, so connections, TLS sessions and DNS
+/// lookups are reused. Cloning an `AliyunClient` is cheap: the clones share the HTTP
+/// client and the credentials provider.
+///
+/// The requests are sent through an `HttpTransport`, a `ReqwestTransport` using the HTTP
+/// client unless another one is set with `AliyunClientBuilder::transport`.
+///
+/// When Aliyun rejects a timestamp (`InvalidTimeStamp.Expired`), the skew of the local
+/// clock is measured from the `Date` header of the rejection and the request is signed
+/// and sent once more; the later timestamps are corrected by the same offset (see
+/// `clock_skew`).
+#[derive(Clone)]
 pub struct AliyunClient {
-    pub access_key_id: String,
-    pub access_key_secret: String,
+    credentials_provider: Arc<dyn CredentialsProvider>,
+    http_client: reqwest::Client,
+    transport: Arc<dyn HttpTransport>,
+    /// The signature scheme used by `send_request`; defaults to `SignatureVersion::V1`.
+    pub signature_version: SignatureVersion,
+    default_region: Option<String>,
+    endpoint_resolver: Arc<dyn EndpointResolver>,
+    retry_policy: RetryPolicy,
+    clock: Clock,
+}
+
+impl std::fmt::Debug for AliyunClient {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("AliyunClient")
+            .field("http_client", &self.http_client)
+            .field("signature_version", &self.signature_version)
+            .field("default_region", &self.default_region)
+            .field("retry_policy", &self.retry_policy)
+            .field("clock_skew", &self.clock_skew())
+            .finish_non_exhaustive()
+    }
 }
 
 impl AliyunClient {
+    /// Returns a builder to configure the credentials, timeouts, proxy, TLS, user agent,
+    /// default region and endpoints of a client.
+    pub fn builder() -> AliyunClientBuilder {
+        AliyunClientBuilder::new()
+    }
+
     /// Creates a new AliyunClient instance with the provided access key and secret.
+    ///
+    /// # Panics
+    ///
+    /// Panics when the HTTP client cannot be initialized (e.g. the TLS backend fails to
+    /// start), like every constructor below. `AliyunClient::builder().build()` returns
+    /// that failure as `AdvancedClientError::Transport` instead.
     pub fn new(access_key_id: String, access_key_secret: String) -> Self {
-        Self {
+        Self::with_credentials_provider(StaticCredentialsProvider::new(Credentials::new(
             access_key_id,
             access_key_secret,
-        }
+        )))
+    }
+
+    /// Creates a new AliyunClient instance with temporary STS credentials, e.g. the
+    /// `Credentials` returned by `STSClient::assume_role`.
+    ///
+    /// The security token is sent as the `SecurityToken` query parameter with the V1
+    /// signature and as the `x-acs-security-token` header with the V3 signature.
+    pub fn with_sts_token(
+        access_key_id: String,
+        access_key_secret: String,
+        security_token: String,
+    ) -> Self {
+        Self::with_credentials_provider(StaticCredentialsProvider::new(
+            Credentials::new(access_key_id, access_key_secret).with_security_token(security_token),
+        ))
+    }
+
+    /// Creates a new AliyunClient instance that asks the given provider for credentials
+    /// before every request.
+    ///
+    /// The client uses the default timeouts and user agent of `AliyunClientBuilder`.
+    ///
+    /// # Panics
+    ///
+    /// Panics when the HTTP client cannot be initialized; use
+    /// `AliyunClient::builder().credentials_provider(provider).build()` to handle it.
+    pub fn with_credentials_provider(provider: impl CredentialsProvider + 'static) -> Self {
+        Self::builder()
+            .credentials_provider(provider)
+            .build()
+            .expect("failed to initialize the HTTP client")
+    }
+
+    /// Creates a new AliyunClient instance using the `DefaultCredentialsChain`.
+    pub fn from_default_chain() -> Self {
+        Self::with_credentials_provider(DefaultCredentialsChain::new())
+    }
+
+    /// Creates a new AliyunClient instance without credentials, which can only be used
+    /// for anonymous operations (see `send_anonymous_request`).
+    pub fn anonymous() -> Self {
+        Self::with_credentials_provider(AnonymousCredentialsProvider)
+    }
+
+    /// Sends every request through the given pre-configured HTTP client instead of
+    /// the default one.
+    pub fn with_http_client(mut self, http_client: reqwest::Client) -> Self {
+        self.transport = Arc::new(ReqwestTransport::new(http_client.clone()));
+        self.http_client = http_client;
+        self
+    }
+
+    /// Sends every request through the given transport, e.g. a `MockTransport` in tests.
+    /// The HTTP client of `http_client` is then no longer used.
+    pub fn with_transport(mut self, transport: impl HttpTransport + 'static) -> Self {
+        self.transport = Arc::new(transport);
+        self
+    }
+
+    /// The HTTP client used to send requests by the default `ReqwestTransport`.
+    ///
+    /// It does not apply once another transport is set with `with_transport` or
+    /// `AliyunClientBuilder::transport`: the requests are then sent by that transport.
+    pub fn http_client(&self) -> &reqwest::Client {
+        &self.http_client
+    }
+
+    /// Sets the region used by operations when none is given explicitly.
+    pub fn with_default_region(mut self, region_id: impl Into<String>) -> Self {
+        self.default_region = Some(region_id.into());
+        self
+    }
+
+    /// The region used by operations when none is given explicitly.
+    pub fn default_region(&self) -> Option<&str> {
+        self.default_region.as_deref()
+    }
+
+    /// The endpoint of `product` (e.g. "ecs") in `region_id`, or in the default region when
+    /// `region_id` is `None`, as resolved by the client's `EndpointResolver`.
+    pub fn endpoint(&self, product: &str, region_id: Option<&str>) -> String {
+        self.endpoint_resolver
+            .resolve_endpoint(product, region_id.or(self.default_region()))
+    }
+
+    /// Sets the policy deciding whether failed requests are sent again.
+    pub fn with_retry_policy(mut self, retry_policy: RetryPolicy) -> Self {
+        self.retry_policy = retry_policy;
+        self
+    }
+
+    /// The policy deciding whether failed requests are sent again.
+    pub fn retry_policy(&self) -> &RetryPolicy {
+        &self.retry_policy
+    }
+
+    /// How far the clock of Aliyun is ahead of the local clock (negative when the local
+    /// clock is ahead), or `None` while no timestamp was rejected.
+    ///
+    /// The skew is measured when Aliyun rejects a request with `InvalidTimeStamp.Expired`
+    /// and is added to the timestamps of the later requests. A skew of more than a few
+    /// seconds means the clock of the host should be synchronized.
+    pub fn clock_skew(&self) -> Option<TimeDelta> {
+        self.clock.skew()
+    }
+
+    /// Sets the signature scheme used for every request sent by this client.
+    pub fn with_signature_version(mut self, signature_version: SignatureVersion) -> Self {
+        self.signature_version = signature_version;
+        self
     }
 
     /// Sends a request to the given endpoint with specific parameters.
     ///
     /// This method automatically adds common parameters (e.g., AccessKeyId, SignatureMethod, Timestamp, etc.),
-    /// computes the signature, and constructs the final URL.
+    /// plus the SecurityToken of temporary credentials, computes the signature, and constructs
+    /// the final URL. The signature scheme is the one configured on the client, see
+    /// `send_request_with_signature` to choose it per call.
+    /// Failed requests are retried according to the client's `RetryPolicy`, signing every
+    /// attempt again.
     ///
     /// # Arguments
     ///
-    /// * `endpoint` - The API endpoint (e.g., "business.aliyuncs.com" or "ecs.aliyuncs.com").
+    /// * `endpoint` - The API endpoint (e.g., "business.aliyuncs.com" or "ecs.cn-hangzhou.aliyuncs.com"),
+    ///   usually obtained from `endpoint`. It may start with a scheme (`http://...`);
+    ///   HTTPS is used otherwise.
     /// * `params` - A BTreeMap containing the specific API parameters.
     ///
     /// # Returns
@@ -39,13 +236,154 @@ impl AliyunClient {
     /// A Result containing the parsed JSON response as a serde_json::Value, or an error.
     pub async fn send_request(
         &self,
+        endpoint: &str,
+        params: BTreeMap<String, String>,
+    ) -> Result<Value, AdvancedClientError> {
+        self.send_request_with_signature(endpoint, params, self.signature_version)
+            .await
+    }
+
+    /// Sends a request like `send_request`, signing it with the given signature scheme.
+    ///
+    /// With `SignatureVersion::V3` the `Action` and `Version` parameters are moved into the
+    /// `x-acs-action` / `x-acs-version` headers and the request is authenticated through the
+    /// `Authorization` header; the remaining parameters stay in the query string.
+    pub async fn send_request_with_signature(
+        &self,
+        endpoint: &str,
+        params: BTreeMap<String, String>,
+        signature_version: SignatureVersion,
+    ) -> Result<Value, AdvancedClientError> {
+        self.with_retries(&params, || async {
+            let credentials = self.credentials_provider.provide_credentials().await?;
+            self.execute_correcting_skew(|now| match signature_version {
+                SignatureVersion::V1 => {
+                    Ok(Self::sign_v1(&credentials, endpoint, params.clone(), now))
+                }
+                SignatureVersion::V3 => Self::sign_v3(&credentials, endpoint, params.clone(), now),
+            })
+            .await
+        })
+        .await
+    }
+
+    /// Sends an anonymous request to the given endpoint with specific parameters.
+    ///
+    /// Some operations, such as STS `AssumeRoleWithSAML` and `AssumeRoleWithOIDC`, authenticate
+    /// the caller through their own parameters. For those the credentials provider is not
+    /// consulted and neither `AccessKeyId` nor `Signature` is added to the request.
+    pub async fn send_anonymous_request(
+        &self,
+        endpoint: &str,
+        params: BTreeMap<String, String>,
+    ) -> Result<Value, AdvancedClientError> {
+        self.with_retries(&params, || {
+            self.execute_correcting_skew(|now| {
+                Ok(Self::prepare_anonymous(endpoint, params.clone(), now))
+            })
+        })
+        .await
+    }
+
+    /// Calls `send` until it succeeds or the retry policy gives up.
+    async fn with_retries<F, Fut>(
+        &self,
+        params: &BTreeMap<String, String>,
+        mut send: F,
+    ) -> Result<Value, AdvancedClientError>
+    where
+        F: FnMut() -> Fut,
+        Fut: Future<Output = Result<Value, AdvancedClientError>>,
+    {
+        let idempotent = retry::is_idempotent(params);
+        let mut attempt = 1;
+        loop {
+            match send().await {
+                Err(e) if self.retry_policy.should_retry(&e, attempt, idempotent) => {
+                    tokio::time::sleep(self.retry_policy.delay(attempt)).await;
+                    attempt += 1;
+                }
+                result => return result,
+            }
+        }
+    }
+
+    /// Sends the request prepared by `sign` with the current time of Aliyun. When its
+    /// timestamp is rejected and the `Date` header of the rejection gives the clock skew,
+    /// the request is prepared with the corrected time and sent once more.
+    async fn execute_correcting_skew<F>(&self, sign: F) -> Result<Value, AdvancedClientError>
+    where
+        F: Fn(DateTime<Utc>) -> Result<SignedRequest, AdvancedClientError>,
+    {
+        let skew = self.clock.skew();
+        match self.execute(sign(self.clock.now())?).await {
+            Err(e) if clock::is_clock_skew_error(&e) && self.clock.skew() != skew => {
+                self.execute(sign(self.clock.now())?).await
+            }
+            result => result,
+        }
+    }
+
+    async fn execute(&self, signed: SignedRequest) -> Result<Value, AdvancedClientError> {
+        // Send HTTP GET request
+        let request = HttpRequest {
+            method: "GET".to_string(),
+            url: signed.url,
+            headers: signed.headers,
+            body: Vec::new(),
+        };
+        let response = self.transport.send(request).await?;
+        if !(200..300).contains(&response.status) {
+            let error = Self::error_from_response(response.status, response.body.clone());
+            if clock::is_clock_skew_error(&error) {
+                if let Some(date) = response.header("date") {
+                    self.clock.correct(date);
+                }
+            }
+            return Err(error);
+        }
+        let body = response.body;
+        serde_json::from_str(&body)
+            .map_err(|source| AdvancedClientError::ResultDeserializationError { source, body })
+    }
+
+    /// Converts an error response into an `AliyunRejectError` when it carries an Aliyun
+    /// error document, or an `HttpStatus` error otherwise.
+    fn error_from_response(status: u16, body: String) -> AdvancedClientError {
+        match serde_json::from_str::<AliyunRejection>(&body) {
+            Ok(rejection) => AdvancedClientError::AliyunRejectError(rejection),
+            Err(_) => AdvancedClientError::HttpStatus { status, body },
+        }
+    }
+
+    /// Adds the common parameters of an unsigned request to the query string.
+    fn prepare_anonymous(
         endpoint: &str,
         mut params: BTreeMap<String, String>,
-    ) -> Result<Value, Box<dyn std::error::Error>> {
+        now: DateTime<Utc>,
+    ) -> SignedRequest {
+        params.insert("SignatureNonce".to_string(), Uuid::new_v4().to_string());
+        let timestamp = now.format("%Y-%m-%dT%H:%M:%SZ").to_string();
+        params.insert("Timestamp".to_string(), timestamp);
+
+        let final_query = crate::utils::build_canonicalized_query(&params);
+        SignedRequest {
+            url: format!("{}?{}", base_url(endpoint).0, final_query),
+            headers: Vec::new(),
+        }
+    }
+
+    /// Adds the V1 common parameters and signature to the query string.
+    fn sign_v1(
+        credentials: &Credentials,
+        endpoint: &str,
+        mut params: BTreeMap<String, String>,
+        now: DateTime<Utc>,
+    ) -> SignedRequest {
         // Insert common parameters
         params
             .entry("AccessKeyId".to_string())
-            .or_insert(self.access_key_id.clone());
+            .or_insert(credentials.access_key_id.clone());
         params
             .entry("SignatureMethod".to_string())
             .or_insert("HMAC-SHA1".to_string());
@@ -53,31 +391,347 @@ impl AliyunClient {
             .entry("SignatureVersion".to_string())
             .or_insert("1.0".to_string());
         params.insert("SignatureNonce".to_string(), Uuid::new_v4().to_string());
-        let timestamp = Utc::now().format("%Y-%m-%dT%H:%M:%SZ").to_string();
+        let timestamp = now.format("%Y-%m-%dT%H:%M:%SZ").to_string();
         params.insert("Timestamp".to_string(), timestamp);
+        if let Some(token) = &credentials.security_token {
+            params.insert("SecurityToken".to_string(), token.clone());
+        }
 
         // Calculate signature using the signing module
-        let signature = signing::calculate_signature(&params, &self.access_key_secret);
+        let signature = signing::calculate_signature(&params, &credentials.access_key_secret);
         params.insert("Signature".to_string(), signature);
 
         // Construct final URL query string
-        let final_query = params
+        let final_query = crate::utils::build_canonicalized_query(&params);
+        SignedRequest {
+            url: format!("{}?{}", base_url(endpoint).0, final_query),
+            headers: Vec::new(),
+        }
+    }
+
+    /// Moves the action into the V3 headers and adds the `Authorization` header.
+    fn sign_v3(
+        credentials: &Credentials,
+        endpoint: &str,
+        mut params: BTreeMap<String, String>,
+        now: DateTime<Utc>,
+    ) -> Result<SignedRequest, AdvancedClientError> {
+        let action = params.remove("Action").ok_or_else(|| {
+            AdvancedClientError::Signature(
+                "V3 signature requires the `Action` parameter".to_string(),
+            )
+        })?;
+        let version = params.remove("Version").ok_or_else(|| {
+            AdvancedClientError::Signature(
+                "V3 signature requires the `Version` parameter".to_string(),
+            )
+        })?;
+        // Responses are always JSON with the V3 signature.
+        params.remove("Format");
+
+        let (base_url, host) = base_url(endpoint);
+        let mut headers = BTreeMap::new();
+        headers.insert("host".to_string(), host.to_string());
+        headers.insert("x-acs-action".to_string(), action);
+        headers.insert("x-acs-version".to_string(), version);
+        headers.insert(
+            "x-acs-date".to_string(),
+            now.format("%Y-%m-%dT%H:%M:%SZ").to_string(),
+        );
+        headers.insert(
+            "x-acs-signature-nonce".to_string(),
+            Uuid::new_v4().simple().to_string(),
+        );
+        headers.insert(
+            "x-acs-content-sha256".to_string(),
+            signing::hash_payload(b""),
+        );
+        if let Some(token) = &credentials.security_token {
+            headers.insert("x-acs-security-token".to_string(), token.clone());
+        }
+
+        let (canonical_request, signed_headers) = signing::build_canonical_request(
+            "GET",
+            "/",
+            &params,
+            &headers,
+            &headers["x-acs-content-sha256"],
+        );
+        let signature =
+            signing::calculate_signature_v3(&canonical_request, &credentials.access_key_secret);
+        headers.insert(
+            "authorization".to_string(),
+            signing::build_authorization_v3(
+                &credentials.access_key_id,
+                &signed_headers,
+                &signature,
+            ),
+        );
+        // `host` is derived from the URL by the HTTP client.
+        headers.remove("host");
+
+        let query = crate::utils::build_canonicalized_query(&params);
+        let url = if query.is_empty() {
+            format!("{}/", base_url)
+        } else {
+            format!("{}/?{}", base_url, query)
+        };
+        Ok(SignedRequest {
+            url,
+            headers: headers.into_iter().collect(),
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::time::Duration;
+
+    use crate::client::transport::{HttpResponse, MockTransport};
+    use crate::test_utils::http_stub::StubServer;
+    use chrono::NaiveDateTime;
+
+    #[test]
+    fn test_sign_v3_moves_action_into_headers() {
+        let credentials = Credentials::new("testid".to_string(), "testsecret".to_string());
+        let params = BTreeMap::from([
+            ("Action".to_string(), "DescribeRegions".to_string()),
+            ("Format".to_string(), "JSON".to_string()),
+            ("Version".to_string(), "2014-05-26".to_string()),
+            ("RegionId".to_string(), "cn-hangzhou".to_string()),
+        ]);
+
+        let signed =
+            AliyunClient::sign_v3(&credentials, "ecs.aliyuncs.com", params, Utc::now()).unwrap();
+        let headers: BTreeMap<String, String> = signed.headers.into_iter().collect();
+
+        assert_eq!(signed.url, "https://ecs.aliyuncs.com/?RegionId=cn-hangzhou");
+        assert_eq!(headers["x-acs-action"], "DescribeRegions");
+        assert_eq!(headers["x-acs-version"], "2014-05-26");
+        assert!(!headers.contains_key("host"));
+        assert!(headers["authorization"].starts_with(
+            "ACS3-HMAC-SHA256 Credential=testid,SignedHeaders=host;x-acs-action;x-acs-content-sha256;x-acs-date;x-acs-signature-nonce;x-acs-version,Signature="
+        ));
+    }
+
+    #[test]
+    fn test_client_is_shareable() {
+        fn assert_shareable<T: Clone + Send + Sync + 'static>() {}
+        assert_shareable::<AliyunClient>();
+    }
+
+    #[tokio::test]
+    async fn test_with_sts_token() {
+        let client = AliyunClient::with_sts_token(
+            "STS.id".to_string(),
+            "secret".to_string(),
+            "token".to_string(),
+        );
+        let credentials = client
+            .credentials_provider
+            .provide_credentials()
+            .await
+            .unwrap();
+        assert_eq!(credentials.access_key_id, "STS.id");
+        assert_eq!(credentials.security_token.as_deref(), Some("token"));
+    }
+
+    #[test]
+    fn test_sign_adds_security_token() {
+        let credentials = Credentials::new("STS.id".to_string(), "secret".to_string())
+            .with_security_token("token".to_string());
+        let params = BTreeMap::from([
+            ("Action".to_string(), "DescribeRegions".to_string()),
+            ("Version".to_string(), "2014-05-26".to_string()),
+        ]);
+
+        let signed =
+            AliyunClient::sign_v1(&credentials, "ecs.aliyuncs.com", params.clone(), Utc::now());
+        assert!(signed.url.contains("&SecurityToken=token&"));
+
+        let signed =
+            AliyunClient::sign_v3(&credentials, "ecs.aliyuncs.com", params, Utc::now()).unwrap();
+        assert!(signed
+            .headers
+            .contains(&("x-acs-security-token".to_string(), "token".to_string())));
+        assert!(signed.headers[0]
+            .1
+            .contains("x-acs-security-token;x-acs-signature-nonce"));
+    }
+
+    #[test]
+    fn test_prepare_anonymous_is_unsigned() {
+        let params = BTreeMap::from([
+            ("Action".to_string(), "AssumeRoleWithOIDC".to_string()),
+            ("Version".to_string(), "2015-04-01".to_string()),
+        ]);
+        let prepared = AliyunClient::prepare_anonymous("sts.aliyuncs.com", params, Utc::now());
+        assert!(prepared
+            .url
+            .starts_with("https://sts.aliyuncs.com?Action=AssumeRoleWithOIDC&"));
+        assert!(prepared.url.contains("&Timestamp="));
+        assert!(!prepared.url.contains("AccessKeyId="));
+        assert!(!prepared.url.contains("Signature="));
+    }
+
+    #[tokio::test]
+    async fn test_anonymous_client_cannot_sign() {
+        let params = BTreeMap::from([
+            ("Action".to_string(), "GetCallerIdentity".to_string()),
+            ("Version".to_string(), "2015-04-01".to_string()),
+        ]);
+        let result = AliyunClient::anonymous()
+            .send_request("sts.aliyuncs.com", params)
+            .await;
+        assert!(matches!(result, Err(AdvancedClientError::Credentials(_))));
+    }
+
+    #[test]
+    fn test_sign_v3_requires_action() {
+        let credentials = Credentials::new("testid".to_string(), "testsecret".to_string());
+        let params = BTreeMap::from([("Version".to_string(), "2014-05-26".to_string())]);
+        assert!(matches!(
+            AliyunClient::sign_v3(&credentials, "ecs.aliyuncs.com", params, Utc::now()),
+            Err(AdvancedClientError::Signature(_))
+        ));
+    }
+
+    #[tokio::test]
+    async fn test_retries_failed_requests() {
+        let proxy = StubServer::start(|_| (502, String::new())).await;
+        let client = AliyunClient::builder()
+            .credentials("id".to_string(), "secret".to_string())
+            .proxy(reqwest::Proxy::all(proxy.url()).unwrap())
+            .retry_policy(
+                RetryPolicy::default()
+                    .with_backoff(Duration::from_millis(1), Duration::from_millis(1)),
+            )
+            .build()
+            .unwrap();
+        let params = BTreeMap::from([("Action".to_string(), "DescribeRegions".to_string())]);
+
+        assert!(client
+            .send_request("ecs.aliyuncs.com", params)
+            .await
+            .is_err());
+        assert_eq!(proxy.requests().len(), 3);
+    }
+
+    #[tokio::test]
+    async fn test_every_attempt_is_signed_again() {
+        let server = StubServer::start(|_| {
+            (
+                503,
+                r#"{"RequestId":"REQ","HostId":"ecs.aliyuncs.com","Code":"ServiceUnavailable"}"#
+                    .to_string(),
+            )
+        })
+        .await;
+        let client = AliyunClient::builder()
+            .credentials("id".to_string(), "secret".to_string())
+            .endpoint_override("ecs", server.url())
+            .retry_policy(
+                RetryPolicy::default()
+                    .with_backoff(Duration::from_millis(1), Duration::from_millis(1)),
+            )
+            .build()
+            .unwrap();
+        let params = BTreeMap::from([("Action".to_string(), "DescribeRegions".to_string())]);
+
+        let result = client
+            .send_request(&client.endpoint("ecs", None), params)
+            .await;
+        assert!(matches!(
+            result,
+            Err(AdvancedClientError::AliyunRejectError(rejection)) if rejection.code == "ServiceUnavailable"
+        ));
+        let nonces: std::collections::BTreeSet<String> = server
+            .requests()
             .iter()
-            .map(|(k, v)| {
-                format!(
-                    "{}={}",
-                    crate::utils::aliyun_percent_encode(k),
-                    crate::utils::aliyun_percent_encode(v)
-                )
+            .map(|request| {
+                let (_, nonce) = request.path.split_once("SignatureNonce=").unwrap();
+                nonce.split('&').next().unwrap().to_string()
             })
-            .collect::<Vec<String>>()
-            .join("&");
-        let url = format!("https://{}?{}", endpoint, final_query);
+            .collect();
+        assert_eq!(nonces.len(), 3);
+    }
 
-        // Send HTTP GET request
-        let client = reqwest::Client::new();
-        let response = client.get(&url).send().await?;
-        let json = response.json::<Value>().await?;
-        Ok(json)
+    #[test]
+    fn test_error_from_response() {
+        let body = r#"{"RequestId":"REQ","HostId":"ecs.aliyuncs.com","Code":"Throttling","Message":"Request was denied due to request throttling."}"#;
+        assert!(matches!(
+            AliyunClient::error_from_response(400, body.to_string()),
+            AdvancedClientError::AliyunRejectError(rejection) if rejection.code == "Throttling"
+        ));
+        assert!(matches!(
+            AliyunClient::error_from_response(502, "Bad Gateway".to_string()),
+            AdvancedClientError::HttpStatus { status: 502, .. }
+        ));
+    }
+
+    #[tokio::test]
+    async fn test_clock_skew_is_corrected() {
+        let server_time = || Utc::now() + TimeDelta::hours(2);
+        let transport = MockTransport::with_handler(move |request| {
+            let timestamp = request.query_params()["Timestamp"].clone();
+            let timestamp =
+                NaiveDateTime::parse_from_str(&timestamp, "%Y-%m-%dT%H:%M:%SZ").unwrap();
+            if (server_time().naive_utc() - timestamp).abs() > TimeDelta::minutes(15) {
+                let body = r#"{"RequestId":"REQ","HostId":"ecs.aliyuncs.com","Code":"InvalidTimeStamp.Expired","Message":"Specified time stamp or date value is expired."}"#;
+                return Ok(HttpResponse::new(400, body).with_header(
+                    "Date",
+                    server_time()
+                        .format("%a, %d %b %Y %H:%M:%S GMT")
+                        .to_string(),
+                ));
+            }
+            Ok(HttpResponse::json(&serde_json::json!({"RequestId": "REQ"})))
+        });
+        let client = AliyunClient::builder()
+            .credentials("id".to_string(), "secret".to_string())
+            .transport(transport.clone())
+            .retry_policy(RetryPolicy::disabled())
+            .build()
+            .unwrap();
+        let params = BTreeMap::from([("Action".to_string(), "DescribeRegions".to_string())]);
+
+        assert_eq!(client.clock_skew(), None);
+        client
+            .send_request("ecs.aliyuncs.com", params.clone())
+            .await
+            .unwrap();
+        let skew = client.clock_skew().unwrap();
+        assert!((skew - TimeDelta::hours(2)).abs() < TimeDelta::seconds(2));
+        assert_eq!(transport.requests().len(), 2);
+
+        // The later requests are sent with the corrected timestamp at once.
+        client
+            .clone()
+            .send_request("ecs.aliyuncs.com", params)
+            .await
+            .unwrap();
+        assert_eq!(transport.requests().len(), 3);
+    }
+
+    #[tokio::test]
+    async fn test_clock_skew_is_retried_once() {
+        let transport = MockTransport::with_handler(|_| {
+            let body = r#"{"RequestId":"REQ","HostId":"ecs.aliyuncs.com","Code":"InvalidTimeStamp.Expired"}"#;
+            Ok(HttpResponse::new(400, body).with_header("Date", "Thu, 01 Jan 2015 00:00:00 GMT"))
+        });
+        let client = AliyunClient::builder()
+            .credentials("id".to_string(), "secret".to_string())
+            .transport(transport.clone())
+            .retry_policy(RetryPolicy::disabled())
+            .build()
+            .unwrap();
+        let params = BTreeMap::from([("Action".to_string(), "DescribeRegions".to_string())]);
+
+        let result = client.send_request("ecs.aliyuncs.com", params).await;
+        assert!(clock::is_clock_skew_error(&result.unwrap_err()));
+        // The second rejection measures the same skew, so the request is not sent again.
+        assert_eq!(transport.requests().len(), 2);
+        assert!(client.clock_skew().unwrap() < TimeDelta::days(-365));
     }
 }
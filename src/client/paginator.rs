@@ -0,0 +1,281 @@
+use std::future::Future;
+
+use futures_util::{stream, Stream, TryStreamExt};
+
+use crate::client::error::AdvancedClientError;
+
+/// The page size used when none is configured.
+pub const DEFAULT_PAGE_SIZE: u32 = 50;
+
+/// How an operation designates the next page of results.
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub enum PaginationStyle {
+    /// `PageNumber` and `PageSize` parameters, with a `TotalCount` in the responses.
+    PageNumber,
+    /// `NextToken` and `MaxResults` parameters, with a `NextToken` in the responses until
+    /// the last page.
+    NextToken,
+}
+
+/// The page requested from the operation.
+#[derive(Clone, PartialEq, Eq, Debug)]
+pub struct PageRequest {
+    /// The 1-based page number, with `PaginationStyle::PageNumber`.
+    pub page_number: Option<u32>,
+    /// The token of the page, with `PaginationStyle::NextToken`; `None` for the first page.
+    pub next_token: Option<String>,
+    pub page_size: u32,
+}
+
+/// A response containing one page of results.
+pub trait PaginatedBody {
+    type Item;
+
+    /// The total number of results of a `PaginationStyle::PageNumber` operation.
+    fn total_count(&self) -> Option<u32> {
+        None
+    }
+
+    /// The token of the next page of a `PaginationStyle::NextToken` operation.
+    fn next_token(&self) -> Option<&str> {
+        None
+    }
+
+    fn item_count(&self) -> usize;
+
+    fn into_items(self) -> Vec<Self::Item>;
+}
+
+/// Fetches every page of a list or describe operation.
+///
+/// `fetch` sends the operation for the requested page. The pages are only requested
+/// while the stream is polled, and the stream ends after the first error.
+///
+/// ```ignore
+/// let instances = client
+///     .ecs("cn-hangzhou")
+///     .describe_instances_paginator(DescribeInstancesRequest::new())
+///     .page_size(100)
+///     .collect_all()
+///     .await?;
+/// ```
+pub struct Paginator<F> {
+    fetch: F,
+    style: PaginationStyle,
+    page_size: u32,
+}
+
+impl<F, Fut, B> Paginator<F>
+where
+    F: FnMut(PageRequest) -> Fut,
+    Fut: Future<Output = Result<B, AdvancedClientError>>,
+    B: PaginatedBody,
+{
+    pub fn new(style: PaginationStyle, fetch: F) -> Self {
+        Self {
+            fetch,
+            style,
+            page_size: DEFAULT_PAGE_SIZE,
+        }
+    }
+
+    /// Sets the number of results requested per page (`DEFAULT_PAGE_SIZE` by default).
+    pub fn page_size(mut self, page_size: u32) -> Self {
+        self.page_size = page_size.max(1);
+        self
+    }
+
+    /// The responses of the pages.
+    pub fn pages(self) -> impl Stream<Item = Result<B, AdvancedClientError>> {
+        let Self {
+            fetch,
+            style,
+            page_size,
+        } = self;
+        let first = PageRequest {
+            page_number: (style == PaginationStyle::PageNumber).then_some(1),
+            next_token: None,
+            page_size,
+        };
+        stream::unfold(
+            (fetch, Some(first)),
+            move |(mut fetch, request)| async move {
+                let request = request?;
+                match fetch(request.clone()).await {
+                    Ok(body) => {
+                        let next = next_page(style, &request, &body);
+                        Some((Ok(body), (fetch, next)))
+                    }
+                    Err(e) => Some((Err(e), (fetch, None))),
+                }
+            },
+        )
+    }
+
+    /// The results of every page.
+    pub fn items(self) -> impl Stream<Item = Result<B::Item, AdvancedClientError>> {
+        self.pages()
+            .map_ok(|body| stream::iter(body.into_items().into_iter().map(Ok)))
+            .try_flatten()
+    }
+
+    /// Fetches every page and returns all the results.
+    pub async fn collect_all(self) -> Result<Vec<B::Item>, AdvancedClientError> {
+        self.items().try_collect().await
+    }
+}
+
+fn next_page<B: PaginatedBody>(
+    style: PaginationStyle,
+    request: &PageRequest,
+    body: &B,
+) -> Option<PageRequest> {
+    match style {
+        PaginationStyle::PageNumber => {
+            let page_number = request.page_number.unwrap_or(1);
+            let fetched = page_number as u64 * request.page_size as u64;
+            let has_more = match body.total_count() {
+                Some(total_count) => fetched < total_count as u64,
+                None => body.item_count() >= request.page_size as usize,
+            };
+            (has_more && body.item_count() > 0).then(|| PageRequest {
+                page_number: Some(page_number + 1),
+                ..request.clone()
+            })
+        }
+        PaginationStyle::NextToken => {
+            body.next_token()
+                .filter(|token| !token.is_empty())
+                .map(|token| PageRequest {
+                    next_token: Some(token.to_string()),
+                    ..request.clone()
+                })
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use futures_util::StreamExt;
+    use std::sync::Mutex;
+
+    #[derive(Debug)]
+    struct Body {
+        items: Vec<u32>,
+        total_count: Option<u32>,
+        next_token: Option<String>,
+    }
+
+    impl PaginatedBody for Body {
+        type Item = u32;
+
+        fn total_count(&self) -> Option<u32> {
+            self.total_count
+        }
+
+        fn next_token(&self) -> Option<&str> {
+            self.next_token.as_deref()
+        }
+
+        fn item_count(&self) -> usize {
+            self.items.len()
+        }
+
+        fn into_items(self) -> Vec<u32> {
+            self.items
+        }
+    }
+
+    /// The results 0..total, served by page number.
+    fn page(request: &PageRequest, total: u32) -> Body {
+        let start = (request.page_number.unwrap() - 1) * request.page_size;
+        Body {
+            items: (start..total.min(start + request.page_size)).collect(),
+            total_count: Some(total),
+            next_token: None,
+        }
+    }
+
+    #[tokio::test]
+    async fn test_page_number() {
+        let requests = Mutex::new(Vec::new());
+        let items = Paginator::new(PaginationStyle::PageNumber, |request: PageRequest| {
+            requests.lock().unwrap().push(request.clone());
+            async move { Ok(page(&request, 7)) }
+        })
+        .page_size(3)
+        .collect_all()
+        .await
+        .unwrap();
+
+        assert_eq!(items, (0..7).collect::<Vec<_>>());
+        let page_numbers: Vec<_> = requests
+            .lock()
+            .unwrap()
+            .iter()
+            .map(|request| request.page_number.unwrap())
+            .collect();
+        assert_eq!(page_numbers, [1, 2, 3]);
+    }
+
+    #[tokio::test]
+    async fn test_page_number_stops_on_exact_multiple() {
+        let items = Paginator::new(
+            PaginationStyle::PageNumber,
+            |request: PageRequest| async move {
+                assert!(request.page_number.unwrap() <= 2);
+                Ok(page(&request, 6))
+            },
+        )
+        .page_size(3)
+        .collect_all()
+        .await
+        .unwrap();
+        assert_eq!(items.len(), 6);
+    }
+
+    #[tokio::test]
+    async fn test_next_token() {
+        let items = Paginator::new(
+            PaginationStyle::NextToken,
+            |request: PageRequest| async move {
+                let (items, next_token) = match request.next_token.as_deref() {
+                    None => (vec![1, 2], Some("page-2")),
+                    Some("page-2") => (vec![3, 4], Some("page-3")),
+                    Some("page-3") => (vec![5], Some("")),
+                    Some(token) => panic!("unexpected token {}", token),
+                };
+                Ok(Body {
+                    items,
+                    total_count: None,
+                    next_token: next_token.map(str::to_string),
+                })
+            },
+        )
+        .collect_all()
+        .await
+        .unwrap();
+        assert_eq!(items, [1, 2, 3, 4, 5]);
+    }
+
+    #[tokio::test]
+    async fn test_stream_ends_after_error() {
+        let mut pages = Box::pin(
+            Paginator::new(
+                PaginationStyle::PageNumber,
+                |request: PageRequest| async move {
+                    match request.page_number {
+                        Some(1) => Ok(page(&request, 10)),
+                        _ => Err(AdvancedClientError::Signature("boom".to_string())),
+                    }
+                },
+            )
+            .page_size(2)
+            .pages(),
+        );
+        assert!(pages.next().await.unwrap().is_ok());
+        assert!(pages.next().await.unwrap().is_err());
+        assert!(pages.next().await.is_none());
+    }
+}
@@ -0,0 +1,200 @@
+use std::{collections::BTreeMap, time::Duration};
+
+use rand::Rng;
+
+use crate::client::error::AdvancedClientError;
+
+/// The error codes retried by default.
+pub const DEFAULT_RETRYABLE_CODES: &[&str] = &[
+    "Throttling",
+    "Throttling.User",
+    "Throttling.Api",
+    "ServiceUnavailable",
+    "InternalError",
+    "UnknownError",
+];
+
+/// The action prefixes of read-only operations, which can always be sent again.
+const READ_ONLY_ACTION_PREFIXES: &[&str] = &["Describe", "Query", "Get", "List"];
+
+/// Decides whether and when a failed request is sent again.
+///
+/// A request is retried at most `max_attempts - 1` times, waiting a random delay between
+/// zero and `base_delay * 2^retry` (capped by `max_delay`) before each retry, and signed
+/// again with a fresh `SignatureNonce` and `Timestamp`.
+///
+/// Throttled requests (`Throttling*` codes, HTTP 429) and connection failures never
+/// reached the service and are always retried. The other retryable codes, 5xx statuses,
+/// timeouts and connection resets are retried only for idempotent requests: read-only
+/// actions (`Describe*`, `Query*`, `Get*`, `List*`) and requests carrying a `ClientToken`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct RetryPolicy {
+    max_attempts: u32,
+    base_delay: Duration,
+    max_delay: Duration,
+    retryable_codes: Vec<String>,
+}
+
+impl Default for RetryPolicy {
+    /// Three attempts, with delays starting at 200ms and capped at 10s.
+    fn default() -> Self {
+        Self {
+            max_attempts: 3,
+            base_delay: Duration::from_millis(200),
+            max_delay: Duration::from_secs(10),
+            retryable_codes: DEFAULT_RETRYABLE_CODES
+                .iter()
+                .map(|code| code.to_string())
+                .collect(),
+        }
+    }
+}
+
+impl RetryPolicy {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// A policy sending every request exactly once.
+    pub fn disabled() -> Self {
+        Self::default().with_max_attempts(1)
+    }
+
+    /// Sets the number of attempts, including the first one.
+    pub fn with_max_attempts(mut self, max_attempts: u32) -> Self {
+        self.max_attempts = max_attempts.max(1);
+        self
+    }
+
+    /// Sets the delay bounds of the exponential backoff.
+    pub fn with_backoff(mut self, base_delay: Duration, max_delay: Duration) -> Self {
+        self.base_delay = base_delay;
+        self.max_delay = max_delay;
+        self
+    }
+
+    /// Replaces the retryable `AliyunRejection::code`s.
+    pub fn with_retryable_codes<I, S>(mut self, codes: I) -> Self
+    where
+        I: IntoIterator<Item = S>,
+        S: Into<String>,
+    {
+        self.retryable_codes = codes.into_iter().map(Into::into).collect();
+        self
+    }
+
+    pub fn max_attempts(&self) -> u32 {
+        self.max_attempts
+    }
+
+    /// Whether a request failing with `error` on its `attempt`-th try (starting at 1)
+    /// should be sent again.
+    pub fn should_retry(
+        &self,
+        error: &AdvancedClientError,
+        attempt: u32,
+        idempotent: bool,
+    ) -> bool {
+        if attempt >= self.max_attempts {
+            return false;
+        }
+        match error {
+            AdvancedClientError::AliyunRejectError(rejection) => {
+                self.retryable_codes.contains(&rejection.code)
+                    && (idempotent || rejection.code.starts_with("Throttling"))
+            }
+            AdvancedClientError::HttpStatus { status, .. } => {
+                *status == 429 || (idempotent && *status >= 500)
+            }
+            AdvancedClientError::Transport(e) => e.is_connect() || idempotent,
+            AdvancedClientError::Timeout(_) => idempotent,
+            _ => false,
+        }
+    }
+
+    /// The delay before the retry following the `attempt`-th try, with full jitter.
+    pub fn delay(&self, attempt: u32) -> Duration {
+        let exponential = self
+            .base_delay
+            .saturating_mul(2u32.saturating_pow(attempt.saturating_sub(1)))
+            .min(self.max_delay);
+        exponential.mul_f64(rand::thread_rng().gen_range(0.0..=1.0))
+    }
+}
+
+/// Whether sending the request twice has the same effect as sending it once.
+pub(crate) fn is_idempotent(params: &BTreeMap<String, String>) -> bool {
+    params.contains_key("ClientToken")
+        || params.get("Action").is_some_and(|action| {
+            READ_ONLY_ACTION_PREFIXES
+                .iter()
+                .any(|prefix| action.starts_with(prefix))
+        })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::client::error::AliyunRejection;
+
+    fn rejection(code: &str) -> AdvancedClientError {
+        AdvancedClientError::AliyunRejectError(AliyunRejection {
+            code: code.to_string(),
+            host_id: "ecs.aliyuncs.com".to_string(),
+            message: String::new(),
+            request_id: "REQ".to_string(),
+            recommend: String::new(),
+        })
+    }
+
+    #[test]
+    fn test_should_retry() {
+        let policy = RetryPolicy::default();
+        assert!(policy.should_retry(&rejection("Throttling.User"), 1, false));
+        assert!(policy.should_retry(&rejection("ServiceUnavailable"), 1, true));
+        assert!(!policy.should_retry(&rejection("ServiceUnavailable"), 1, false));
+        assert!(!policy.should_retry(&rejection("InvalidParameter"), 1, true));
+        assert!(!policy.should_retry(&rejection("Throttling"), 3, true));
+
+        let status = |status| AdvancedClientError::HttpStatus {
+            status,
+            body: String::new(),
+        };
+        assert!(policy.should_retry(&status(429), 1, false));
+        assert!(policy.should_retry(&status(503), 1, true));
+        assert!(!policy.should_retry(&status(503), 1, false));
+        assert!(!policy.should_retry(&status(404), 1, true));
+
+        assert!(!RetryPolicy::disabled().should_retry(&rejection("Throttling"), 1, true));
+    }
+
+    #[test]
+    fn test_delay_is_bounded() {
+        let policy = RetryPolicy::default()
+            .with_backoff(Duration::from_millis(100), Duration::from_millis(300));
+        for attempt in 1..10 {
+            let delay = policy.delay(attempt);
+            assert!(
+                delay
+                    <= Duration::from_millis(100 * 2u64.pow(attempt - 1))
+                        .min(Duration::from_millis(300))
+            );
+        }
+    }
+
+    #[test]
+    fn test_is_idempotent() {
+        let params = |pairs: &[(&str, &str)]| -> BTreeMap<String, String> {
+            pairs
+                .iter()
+                .map(|(k, v)| (k.to_string(), v.to_string()))
+                .collect()
+        };
+        assert!(is_idempotent(&params(&[("Action", "DescribeInstances")])));
+        assert!(!is_idempotent(&params(&[("Action", "RunInstances")])));
+        assert!(is_idempotent(&params(&[
+            ("Action", "RunInstances"),
+            ("ClientToken", "token")
+        ])));
+    }
+}
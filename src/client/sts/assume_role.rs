@@ -0,0 +1,18 @@
+use serde::{Deserialize, Serialize};
+
+use crate::client::sts::credentials::StsCredentials;
+
+#[derive(Serialize, Deserialize, Debug)]
+#[serde(rename_all = "PascalCase")]
+pub struct AssumedRoleUser {
+    pub assumed_role_id: String,
+    pub arn: String,
+}
+
+#[derive(Serialize, Deserialize, Debug)]
+#[serde(rename_all = "PascalCase")]
+pub struct AssumeRoleBody {
+    pub request_id: String,
+    pub credentials: StsCredentials,
+    pub assumed_role_user: AssumedRoleUser,
+}
@@ -0,0 +1,24 @@
+use serde::{Deserialize, Serialize};
+
+use crate::client::sts::{assume_role::AssumedRoleUser, credentials::StsCredentials};
+
+#[derive(Serialize, Deserialize, Debug)]
+#[serde(rename_all = "PascalCase")]
+pub struct OIDCTokenInfo {
+    pub subject: String,
+    pub issuer: String,
+    pub client_ids: String,
+    pub expiration_time: Option<String>,
+    pub issuance_time: Option<String>,
+    pub verification_info: Option<String>,
+}
+
+#[derive(Serialize, Deserialize, Debug)]
+#[serde(rename_all = "PascalCase")]
+pub struct AssumeRoleWithOIDCBody {
+    pub request_id: String,
+    pub credentials: StsCredentials,
+    pub assumed_role_user: AssumedRoleUser,
+    #[serde(rename = "OIDCTokenInfo")]
+    pub oidc_token_info: Option<OIDCTokenInfo>,
+}
@@ -0,0 +1,22 @@
+use serde::{Deserialize, Serialize};
+
+use crate::client::sts::{assume_role::AssumedRoleUser, credentials::StsCredentials};
+
+#[derive(Serialize, Deserialize, Debug)]
+#[serde(rename_all = "PascalCase")]
+pub struct SAMLAssertionInfo {
+    pub subject_type: String,
+    pub subject: String,
+    pub recipient: String,
+    pub issuer: String,
+}
+
+#[derive(Serialize, Deserialize, Debug)]
+#[serde(rename_all = "PascalCase")]
+pub struct AssumeRoleWithSAMLBody {
+    pub request_id: String,
+    pub credentials: StsCredentials,
+    pub assumed_role_user: AssumedRoleUser,
+    #[serde(rename = "SAMLAssertionInfo")]
+    pub saml_assertion_info: Option<SAMLAssertionInfo>,
+}
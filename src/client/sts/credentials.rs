@@ -0,0 +1,32 @@
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+
+use crate::client::credentials::Credentials;
+
+/// Temporary credentials issued by STS, shared by the `AssumeRole*` responses
+/// (and by the ECS instance metadata service, which uses the same shape).
+#[derive(Serialize, Deserialize, Clone)]
+#[serde(rename_all = "PascalCase")]
+pub struct StsCredentials {
+    pub access_key_id: String,
+    pub access_key_secret: String,
+    pub security_token: String,
+    pub expiration: DateTime<Utc>,
+}
+
+impl std::fmt::Debug for StsCredentials {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        Credentials::from(self.clone()).fmt(f)
+    }
+}
+
+impl From<StsCredentials> for Credentials {
+    fn from(credentials: StsCredentials) -> Self {
+        Credentials {
+            access_key_id: credentials.access_key_id,
+            access_key_secret: credentials.access_key_secret,
+            security_token: Some(credentials.security_token),
+            expiration: Some(credentials.expiration),
+        }
+    }
+}
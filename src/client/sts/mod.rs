@@ -1,11 +1,25 @@
+pub mod assume_role;
+pub mod assume_role_with_oidc;
+pub mod assume_role_with_saml;
 pub mod caller_identity;
+pub mod credentials;
+
+use std::time::Duration;
 
 use crate::{
     client::{
-        error::AdvancedClientError, sts::caller_identity::CallerIdentityBody,
-        utils::parse_json_value, AliyunClient,
+        credentials::{oidc::ENV_OIDC_TOKEN_FILE, CredentialsError},
+        error::AdvancedClientError,
+        sts::{
+            assume_role::AssumeRoleBody, assume_role_with_oidc::AssumeRoleWithOIDCBody,
+            assume_role_with_saml::AssumeRoleWithSAMLBody, caller_identity::CallerIdentityBody,
+        },
+        utils::parse_json_value,
+        AliyunClient,
+    },
+    services::sts::{
+        assume_role, assume_role_with_oidc, assume_role_with_saml, get_caller_identity,
     },
-    services::sts::get_caller_identity,
 };
 
 /// A thin, non-owning client for STS (Security Token Service) operations.
@@ -30,10 +44,119 @@ impl<'a> STSClient<'a> {
     /// Errors from the HTTP client or JSON deserialization are propagated as
     /// `AdvancedClientError`.
     pub async fn get_caller_identity(&self) -> Result<CallerIdentityBody, AdvancedClientError> {
-        let response = get_caller_identity(&self.client).await?;
+        let response = get_caller_identity(self.client).await?;
         let parsed = parse_json_value::<CallerIdentityBody>(response)?;
         Result::Ok(parsed)
     }
+
+    /// Call the STS `AssumeRole` operation and return the temporary credentials
+    /// of the role as a deserialized `AssumeRoleBody`.
+    ///
+    /// `duration` is the validity period of the credentials, rounded down to whole
+    /// seconds; Aliyun defaults to one hour when it is `None`. `policy` further
+    /// restricts the permissions of the returned credentials.
+    pub async fn assume_role(
+        &self,
+        role_arn: &str,
+        role_session_name: &str,
+        duration: Option<Duration>,
+        policy: Option<&str>,
+    ) -> Result<AssumeRoleBody, AdvancedClientError> {
+        let response = assume_role(
+            self.client,
+            role_arn,
+            role_session_name,
+            duration.map(|d| d.as_secs()),
+            policy,
+        )
+        .await?;
+        let parsed = parse_json_value::<AssumeRoleBody>(response)?;
+        Result::Ok(parsed)
+    }
+
+    /// Call the anonymous STS `AssumeRoleWithSAML` operation, exchanging the Base64
+    /// encoded SAML assertion of an SSO login for the temporary credentials of a role.
+    ///
+    /// The credentials of the parent client are not used.
+    pub async fn assume_role_with_saml(
+        &self,
+        saml_provider_arn: &str,
+        role_arn: &str,
+        saml_assertion: &str,
+        duration: Option<Duration>,
+        policy: Option<&str>,
+    ) -> Result<AssumeRoleWithSAMLBody, AdvancedClientError> {
+        let response = assume_role_with_saml(
+            self.client,
+            saml_provider_arn,
+            role_arn,
+            saml_assertion,
+            duration.map(|d| d.as_secs()),
+            policy,
+        )
+        .await?;
+        let parsed = parse_json_value::<AssumeRoleWithSAMLBody>(response)?;
+        Result::Ok(parsed)
+    }
+
+    /// Call the anonymous STS `AssumeRoleWithOIDC` operation with the OIDC token read
+    /// from the file named by `ALIBABA_CLOUD_OIDC_TOKEN_FILE`, as mounted by RRSA.
+    ///
+    /// The credentials of the parent client are not used.
+    pub async fn assume_role_with_oidc(
+        &self,
+        oidc_provider_arn: &str,
+        role_arn: &str,
+        role_session_name: Option<&str>,
+        duration: Option<Duration>,
+        policy: Option<&str>,
+    ) -> Result<AssumeRoleWithOIDCBody, AdvancedClientError> {
+        let path = std::env::var(ENV_OIDC_TOKEN_FILE).map_err(|e| {
+            CredentialsError::NotConfigured(format!("{}: {}", ENV_OIDC_TOKEN_FILE, e))
+        })?;
+        let oidc_token = std::fs::read_to_string(&path)
+            .map_err(|source| CredentialsError::Io {
+                path: path.into(),
+                source,
+            })?
+            .trim()
+            .to_string();
+        self.assume_role_with_oidc_token(
+            oidc_provider_arn,
+            role_arn,
+            &oidc_token,
+            role_session_name,
+            duration,
+            policy,
+        )
+        .await
+    }
+
+    /// Call the anonymous STS `AssumeRoleWithOIDC` operation with the given OIDC token.
+    ///
+    /// The credentials of the parent client are not used.
+    pub async fn assume_role_with_oidc_token(
+        &self,
+        oidc_provider_arn: &str,
+        role_arn: &str,
+        oidc_token: &str,
+        role_session_name: Option<&str>,
+        duration: Option<Duration>,
+        policy: Option<&str>,
+    ) -> Result<AssumeRoleWithOIDCBody, AdvancedClientError> {
+        let response = assume_role_with_oidc(
+            self.client,
+            oidc_provider_arn,
+            role_arn,
+            oidc_token,
+            role_session_name,
+            duration.map(|d| d.as_secs()),
+            policy,
+        )
+        .await?;
+        let parsed = parse_json_value::<AssumeRoleWithOIDCBody>(response)?;
+        Result::Ok(parsed)
+    }
 }
 
 impl AliyunClient {
@@ -50,9 +173,18 @@ impl AliyunClient {
 #[cfg(test)]
 mod tests {
     use claims::assert_matches;
+    use serde_json::json;
 
+    use super::{AssumeRoleBody, AssumeRoleWithOIDCBody, AssumeRoleWithSAMLBody};
     use crate::{
-        client::error::AdvancedClientError,
+        client::{
+            credentials::Credentials,
+            error::AdvancedClientError,
+            transport::{HttpResponse, MockTransport},
+            utils::parse_json_value,
+            AliyunClient,
+        },
+        mock_server::MockAliyunServer,
         test_multiple_clients,
         test_utils::{create_aliyun_client, EMPTY, GLOBAL_TEST_SECRETS, INVALID},
     };
@@ -72,7 +204,9 @@ mod tests {
 
     #[tokio::test]
     async fn test_get_caller_identity() {
+        let server = MockAliyunServer::start().await;
         test_multiple_clients! {
+            server: server,
             [EMPTY => "EMPTY", INVALID => "INVALID"],
             |client, name| async {
                 let result = client.sts().get_caller_identity().await;
@@ -81,4 +215,172 @@ mod tests {
             }
         }
     }
+
+    #[test]
+    fn test_parse_assume_role_body() {
+        let response = json!({
+            "RequestId": "6894B13B-6D71-4EF5-88FA-F32781734A7F",
+            "AssumedRoleUser": {
+                "AssumedRoleId": "344584339364951186:alice",
+                "Arn": "acs:ram::123456789012****:role/Alice/alice"
+            },
+            "Credentials": {
+                "SecurityToken": "********",
+                "AccessKeyId": "STS.L4aBSCSJVMuKg5U1****",
+                "AccessKeySecret": "wyLTSmsyPGP1ohvvw8xYgB29dlGI8KMiH2pK****",
+                "Expiration": "2015-04-09T11:52:19Z"
+            }
+        });
+        let body = parse_json_value::<AssumeRoleBody>(response).unwrap();
+        assert_eq!(
+            body.assumed_role_user.assumed_role_id,
+            "344584339364951186:alice"
+        );
+
+        let credentials = Credentials::from(body.credentials);
+        assert_eq!(credentials.access_key_id, "STS.L4aBSCSJVMuKg5U1****");
+        assert_eq!(credentials.security_token.as_deref(), Some("********"));
+        assert_eq!(
+            credentials.expiration.unwrap().to_rfc3339(),
+            "2015-04-09T11:52:19+00:00"
+        );
+    }
+
+    #[test]
+    fn test_parse_assume_role_rejection() {
+        let response = json!({
+            "RequestId": "7A3B4E8D-9C2F-4B1A-8E6D-5F0C1D2E3A4B",
+            "HostId": "sts.aliyuncs.com",
+            "Code": "NoPermission",
+            "Message": "You are not authorized to do this action. You should be authorized by RAM.",
+            "Recommend": "https://api.aliyun.com/troubleshoot?q=NoPermission"
+        });
+        let result = parse_json_value::<AssumeRoleBody>(response);
+        assert_matches!(
+            result,
+            Err(AdvancedClientError::AliyunRejectError(ref rejection)) if rejection.code == "NoPermission"
+        );
+    }
+
+    #[tokio::test]
+    async fn test_assume_role() {
+        let server = MockAliyunServer::start().await;
+        test_multiple_clients! {
+            server: server,
+            [EMPTY => "EMPTY", INVALID => "INVALID"],
+            |client, name| async {
+                let result = client
+                    .sts()
+                    .assume_role("acs:ram::123456789012****:role/testrole", "alibabacloud-rust", None, None)
+                    .await;
+                println!("{} Result: {:#?}", name, result);
+                assert_matches!(result, Err(AdvancedClientError::AliyunRejectError(_)));
+            }
+        }
+    }
+
+    #[test]
+    fn test_parse_assume_role_with_saml_body() {
+        let response = json!({
+            "RequestId": "4EB2E6F5-AF0B-4DE0-9C9B-0A95A4EAEA91",
+            "SAMLAssertionInfo": {
+                "SubjectType": "persistent",
+                "Issuer": "http://example.com/adfs/services/trust",
+                "Recipient": "https://signin.aliyun.com/saml-role/SSO",
+                "Subject": "alice@example.com"
+            },
+            "AssumedRoleUser": {
+                "AssumedRoleId": "344584339364951186:alice@example.com",
+                "Arn": "acs:ram::123456789012****:role/testrole/alice@example.com"
+            },
+            "Credentials": {
+                "SecurityToken": "********",
+                "Expiration": "2015-04-09T11:52:19Z",
+                "AccessKeySecret": "wyLTSmsyPGP1ohvvw8xYgB29dlGI8KMiH2pK****",
+                "AccessKeyId": "STS.NUgYrLnoC37mZZCNnAbez****"
+            }
+        });
+        let body = parse_json_value::<AssumeRoleWithSAMLBody>(response).unwrap();
+        assert_eq!(
+            body.credentials.access_key_id,
+            "STS.NUgYrLnoC37mZZCNnAbez****"
+        );
+        assert_eq!(
+            body.saml_assertion_info.unwrap().subject,
+            "alice@example.com"
+        );
+    }
+
+    #[test]
+    fn test_parse_assume_role_with_oidc_body() {
+        let response = json!({
+            "RequestId": "3D57EAD2-8723-1F26-B69C-F8707D8B565D",
+            "OIDCTokenInfo": {
+                "Subject": "KryrkIdjylZb7agUgCEf****",
+                "Issuer": "https://dev-xxxxxx.okta.com",
+                "ClientIds": "496271242565057****",
+                "ExpirationTime": "2021-10-20T04:27:09Z",
+                "IssuanceTime": "2021-10-20T03:27:09Z",
+                "VerificationInfo": "Success"
+            },
+            "AssumedRoleUser": {
+                "AssumedRoleId": "33157794895460****",
+                "Arn": "acs:ram::113511544585****:role/testoidc/TestOidcAssumedRoleSession"
+            },
+            "Credentials": {
+                "SecurityToken": "********",
+                "Expiration": "2021-10-20T04:27:09Z",
+                "AccessKeySecret": "CVwjCkNzTMupZ8NbTCxCBRq3K16jtcWFTJAyBEv2****",
+                "AccessKeyId": "STS.NUgYrLnoC37mZZCNnAbez****"
+            }
+        });
+        let body = parse_json_value::<AssumeRoleWithOIDCBody>(response).unwrap();
+        assert_eq!(
+            body.assumed_role_user.arn,
+            "acs:ram::113511544585****:role/testoidc/TestOidcAssumedRoleSession"
+        );
+        assert_eq!(
+            body.oidc_token_info.unwrap().client_ids,
+            "496271242565057****"
+        );
+    }
+
+    #[tokio::test]
+    async fn test_assume_role_with_oidc_token() {
+        let transport = MockTransport::new();
+        transport.push_response(HttpResponse::new(
+            400,
+            json!({
+                "RequestId": "7B5F1E5D-6B4A-4E7F-9C3D-2A8E1F0B4C6D",
+                "HostId": "sts.aliyuncs.com",
+                "Code": "InvalidParameter.OIDCToken",
+                "Message": "The parameter OIDCToken is invalid.",
+                "Recommend": "https://api.aliyun.com/troubleshoot?q=InvalidParameter.OIDCToken"
+            })
+            .to_string(),
+        ));
+        // Anonymous operation: an invalid token is rejected whatever the client credentials are.
+        let result = AliyunClient::anonymous()
+            .with_transport(transport.clone())
+            .sts()
+            .assume_role_with_oidc_token(
+                "acs:ram::123456789012****:oidc-provider/TestOidcIdp",
+                "acs:ram::123456789012****:role/testrole",
+                "invalid-token",
+                Some("alibabacloud-rust"),
+                None,
+                None,
+            )
+            .await;
+        assert_matches!(
+            result,
+            Err(AdvancedClientError::AliyunRejectError(ref rejection)) if rejection.code == "InvalidParameter.OIDCToken"
+        );
+
+        let params = transport.requests()[0].query_params();
+        assert_eq!(params["Action"], "AssumeRoleWithOIDC");
+        assert_eq!(params["OIDCToken"], "invalid-token");
+        assert!(!params.contains_key("AccessKeyId"));
+        assert!(!params.contains_key("Signature"));
+    }
 }
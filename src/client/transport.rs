@@ -0,0 +1,338 @@
+use std::{
+    collections::{BTreeMap, VecDeque},
+    sync::{Arc, Mutex},
+};
+
+use crate::client::error::AdvancedClientError;
+
+/// A signed HTTP request, as handed to an `HttpTransport`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct HttpRequest {
+    pub method: String,
+    /// The full URL, including the query string.
+    pub url: String,
+    /// The headers added by the client; the transport adds `host` and its own headers
+    /// (e.g. `user-agent`).
+    pub headers: Vec<(String, String)>,
+    pub body: Vec<u8>,
+}
+
+impl HttpRequest {
+    /// The percent-decoded parameters of the query string.
+    pub fn query_params(&self) -> BTreeMap<String, String> {
+        match self.url.split_once('?') {
+            Some((_, query)) => crate::utils::parse_query(query),
+            None => BTreeMap::new(),
+        }
+    }
+
+    /// The value of the header `name`, compared case-insensitively.
+    pub fn header(&self, name: &str) -> Option<&str> {
+        self.headers
+            .iter()
+            .find(|(header, _)| header.eq_ignore_ascii_case(name))
+            .map(|(_, value)| value.as_str())
+    }
+}
+
+/// The response returned by an `HttpTransport`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct HttpResponse {
+    pub status: u16,
+    pub headers: Vec<(String, String)>,
+    pub body: String,
+}
+
+impl HttpResponse {
+    pub fn new(status: u16, body: impl Into<String>) -> Self {
+        Self {
+            status,
+            headers: Vec::new(),
+            body: body.into(),
+        }
+    }
+
+    /// A `200 OK` response with the given JSON body.
+    pub fn json(body: &serde_json::Value) -> Self {
+        Self::new(200, body.to_string())
+            .with_header("content-type", "application/json;charset=utf-8")
+    }
+
+    pub fn with_header(mut self, name: impl Into<String>, value: impl Into<String>) -> Self {
+        self.headers.push((name.into(), value.into()));
+        self
+    }
+
+    /// The value of the header `name`, compared case-insensitively.
+    pub fn header(&self, name: &str) -> Option<&str> {
+        self.headers
+            .iter()
+            .find(|(header, _)| header.eq_ignore_ascii_case(name))
+            .map(|(_, value)| value.as_str())
+    }
+}
+
+/// Sends the signed requests of an `AliyunClient` and returns the raw responses.
+///
+/// The default transport is `ReqwestTransport`; `MockTransport` answers from memory for
+/// offline tests. Transport failures are returned as `AdvancedClientError::Transport` or
+/// `AdvancedClientError::Timeout`, which the retry policy may retry.
+#[async_trait::async_trait]
+pub trait HttpTransport: Send + Sync {
+    async fn send(&self, request: HttpRequest) -> Result<HttpResponse, AdvancedClientError>;
+}
+
+/// Sends the requests with a pooled `reqwest::Client`.
+#[derive(Debug, Clone)]
+pub struct ReqwestTransport {
+    http_client: reqwest::Client,
+}
+
+impl ReqwestTransport {
+    pub fn new(http_client: reqwest::Client) -> Self {
+        Self { http_client }
+    }
+
+    pub fn http_client(&self) -> &reqwest::Client {
+        &self.http_client
+    }
+}
+
+#[async_trait::async_trait]
+impl HttpTransport for ReqwestTransport {
+    async fn send(&self, request: HttpRequest) -> Result<HttpResponse, AdvancedClientError> {
+        let method = reqwest::Method::from_bytes(request.method.as_bytes()).map_err(|_| {
+            AdvancedClientError::InvalidRequest(format!("invalid HTTP method {}", request.method))
+        })?;
+        let mut builder = self.http_client.request(method, &request.url);
+        for (name, value) in request.headers {
+            builder = builder.header(name, value);
+        }
+        if !request.body.is_empty() {
+            builder = builder.body(request.body);
+        }
+        let response = builder.send().await?;
+        let status = response.status().as_u16();
+        let headers = response
+            .headers()
+            .iter()
+            .map(|(name, value)| {
+                (
+                    name.to_string(),
+                    String::from_utf8_lossy(value.as_bytes()).into_owned(),
+                )
+            })
+            .collect();
+        let body = response.text().await?;
+        Ok(HttpResponse {
+            status,
+            headers,
+            body,
+        })
+    }
+}
+
+type Handler = dyn Fn(&HttpRequest) -> Result<HttpResponse, AdvancedClientError> + Send + Sync;
+
+#[derive(Default)]
+struct MockState {
+    responses: VecDeque<Result<HttpResponse, AdvancedClientError>>,
+    requests: Vec<HttpRequest>,
+}
+
+/// An in-memory transport answering with scripted responses and recording the requests,
+/// to test operations offline and assert on the signed requests.
+///
+/// The scripted responses are returned in order; once they are exhausted the handler
+/// answers, if any. Clones share the script and the recorded requests, so a clone can be
+/// kept to inspect the requests after handing the transport to a client.
+///
+/// ```ignore
+/// let transport = MockTransport::new();
+/// transport.push_response(HttpResponse::json(&json!({"RequestId": "REQ", "Regions": {"Region": []}})));
+/// let client = AliyunClient::builder()
+///     .credentials(id, secret)
+///     .transport(transport.clone())
+///     .build()?;
+/// client.ecs("cn-hangzhou").describe_regions().await?;
+/// assert_eq!(transport.requests()[0].query_params()["Action"], "DescribeRegions");
+/// ```
+#[derive(Clone, Default)]
+pub struct MockTransport {
+    state: Arc<Mutex<MockState>>,
+    handler: Option<Arc<Handler>>,
+}
+
+impl std::fmt::Debug for MockTransport {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let state = self.state.lock().unwrap();
+        f.debug_struct("MockTransport")
+            .field("pending_responses", &state.responses.len())
+            .field("requests", &state.requests.len())
+            .finish_non_exhaustive()
+    }
+}
+
+impl MockTransport {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// A transport answering every request without a scripted response with `handler`.
+    pub fn with_handler(
+        handler: impl Fn(&HttpRequest) -> Result<HttpResponse, AdvancedClientError>
+            + Send
+            + Sync
+            + 'static,
+    ) -> Self {
+        Self {
+            state: Arc::default(),
+            handler: Some(Arc::new(handler)),
+        }
+    }
+
+    /// Answers the next request with `response`.
+    pub fn push_response(&self, response: HttpResponse) -> &Self {
+        self.state.lock().unwrap().responses.push_back(Ok(response));
+        self
+    }
+
+    /// Fails the next request with `error`, e.g. `AdvancedClientError::HttpStatus`.
+    pub fn push_error(&self, error: AdvancedClientError) -> &Self {
+        self.state.lock().unwrap().responses.push_back(Err(error));
+        self
+    }
+
+    /// The requests sent so far, in order.
+    pub fn requests(&self) -> Vec<HttpRequest> {
+        self.state.lock().unwrap().requests.clone()
+    }
+}
+
+#[async_trait::async_trait]
+impl HttpTransport for MockTransport {
+    /// # Panics
+    ///
+    /// When no response is scripted and there is no handler.
+    async fn send(&self, request: HttpRequest) -> Result<HttpResponse, AdvancedClientError> {
+        let scripted = {
+            let mut state = self.state.lock().unwrap();
+            state.requests.push(request.clone());
+            state.responses.pop_front()
+        };
+        match (scripted, &self.handler) {
+            (Some(response), _) => response,
+            (None, Some(handler)) => handler(&request),
+            (None, None) => panic!("MockTransport has no response for {}", request.url),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    use crate::client::{retry::RetryPolicy, AliyunClient};
+    use crate::signing;
+
+    #[tokio::test]
+    async fn test_invalid_method_is_an_invalid_request() {
+        let transport = ReqwestTransport::new(reqwest::Client::new());
+        let request = HttpRequest {
+            method: "NOT A METHOD".to_string(),
+            url: "https://ecs.aliyuncs.com".to_string(),
+            headers: Vec::new(),
+            body: Vec::new(),
+        };
+        assert!(matches!(
+            transport.send(request).await,
+            Err(AdvancedClientError::InvalidRequest(_))
+        ));
+    }
+
+    fn client(transport: &MockTransport) -> AliyunClient {
+        AliyunClient::builder()
+            .credentials("testid".to_string(), "testsecret".to_string())
+            .transport(transport.clone())
+            .retry_policy(RetryPolicy::disabled())
+            .build()
+            .unwrap()
+    }
+
+    #[test]
+    fn test_query_params() {
+        let request = HttpRequest {
+            method: "GET".to_string(),
+            url: "https://ecs.aliyuncs.com/?Action=DescribeRegions&Filters=%5B%22a%22%5D&Empty="
+                .to_string(),
+            headers: vec![("X-Acs-Action".to_string(), "DescribeRegions".to_string())],
+            body: Vec::new(),
+        };
+        let params = request.query_params();
+        assert_eq!(params["Action"], "DescribeRegions");
+        assert_eq!(params["Filters"], r#"["a"]"#);
+        assert_eq!(params["Empty"], "");
+        assert_eq!(request.header("x-acs-action"), Some("DescribeRegions"));
+    }
+
+    #[tokio::test]
+    async fn test_mock_transport_records_signed_request() {
+        let transport = MockTransport::new();
+        transport.push_response(HttpResponse::json(&json!({"RequestId": "REQ"})));
+        let params = BTreeMap::from([
+            ("Action".to_string(), "DescribeRegions".to_string()),
+            ("Version".to_string(), "2014-05-26".to_string()),
+        ]);
+
+        let client = client(&transport);
+        let body = client
+            .send_request(&client.endpoint("ecs", Some("cn-hangzhou")), params)
+            .await
+            .unwrap();
+        assert_eq!(body["RequestId"], "REQ");
+
+        let requests = transport.requests();
+        assert_eq!(requests.len(), 1);
+        assert_eq!(requests[0].method, "GET");
+        assert!(requests[0]
+            .url
+            .starts_with("https://ecs.cn-hangzhou.aliyuncs.com?"));
+        let mut params = requests[0].query_params();
+        assert_eq!(params["AccessKeyId"], "testid");
+        let signature = params.remove("Signature").unwrap();
+        assert_eq!(
+            signature,
+            signing::calculate_signature(&params, "testsecret")
+        );
+    }
+
+    #[tokio::test]
+    async fn test_mock_transport_script_and_handler() {
+        let transport = MockTransport::with_handler(|_| Ok(HttpResponse::new(200, "{}")));
+        transport.push_response(HttpResponse::new(
+            400,
+            r#"{"RequestId":"REQ","HostId":"ecs.aliyuncs.com","Code":"InvalidParameter"}"#,
+        ));
+        transport.push_error(AdvancedClientError::HttpStatus {
+            status: 502,
+            body: String::new(),
+        });
+        let client = client(&transport);
+        let params = || BTreeMap::from([("Action".to_string(), "DescribeRegions".to_string())]);
+
+        assert!(matches!(
+            client.send_request("ecs.aliyuncs.com", params()).await,
+            Err(AdvancedClientError::AliyunRejectError(rejection)) if rejection.code == "InvalidParameter"
+        ));
+        assert!(matches!(
+            client.send_request("ecs.aliyuncs.com", params()).await,
+            Err(AdvancedClientError::HttpStatus { status: 502, .. })
+        ));
+        assert!(client
+            .send_request("ecs.aliyuncs.com", params())
+            .await
+            .is_ok());
+        assert_eq!(transport.requests().len(), 3);
+    }
+}
@@ -1,6 +1,6 @@
-use std::fmt::Debug;
+use std::{collections::BTreeMap, fmt::Debug};
 
-use serde::Deserialize;
+use serde::{Deserialize, Deserializer};
 
 use crate::client::error::{AdvancedClientError, AliyunRejection};
 
@@ -11,11 +11,32 @@ enum ResponseFromAliyun<R: Debug> {
     Rejected(AliyunRejection),
 }
 
+/// Parses the body of a successful response into `R`, or into an `AliyunRejectError` when
+/// it is an error document not matching `R`.
+///
+/// The rejections answered with an error status already fail in `AliyunClient::send_request`,
+/// so the model is tried first: a success body carrying `Code`, `HostId` and `RequestId`
+/// is not a rejection.
 pub fn parse_json_value<R: Debug + for<'de> Deserialize<'de>>(
     value: serde_json::Value,
 ) -> Result<R, AdvancedClientError> {
-    match serde_json::from_value::<ResponseFromAliyun<R>>(value)? {
-        ResponseFromAliyun::Normal(result) => Ok(result),
-        ResponseFromAliyun::Rejected(err) => Err(AdvancedClientError::AliyunRejectError(err)),
+    match ResponseFromAliyun::<R>::deserialize(&value) {
+        Ok(ResponseFromAliyun::Normal(result)) => Ok(result),
+        Ok(ResponseFromAliyun::Rejected(err)) => Err(AdvancedClientError::AliyunRejectError(err)),
+        Err(source) => Err(AdvancedClientError::ResultDeserializationError {
+            source,
+            body: value.to_string(),
+        }),
     }
 }
+
+/// Deserializes the lists of RPC responses, which are wrapped in an object with a single
+/// key, e.g. `"Regions": {"Region": [...]}`, into the list itself.
+pub(crate) fn unwrap_list<'de, D, T>(deserializer: D) -> Result<Vec<T>, D::Error>
+where
+    D: Deserializer<'de>,
+    T: Deserialize<'de>,
+{
+    let wrapper = BTreeMap::<String, Vec<T>>::deserialize(deserializer)?;
+    Ok(wrapper.into_values().next().unwrap_or_default())
+}
@@ -1,4 +1,6 @@
 pub mod client;
+#[cfg(any(test, feature = "mock-server"))]
+pub mod mock_server;
 pub mod services;
 pub mod signing;
 pub mod utils;
@@ -1,4 +1,4 @@
-use crate::client::AliyunClient;
+use crate::client::{error::AdvancedClientError, AliyunClient};
 use serde_json::Value;
 use std::collections::BTreeMap;
 
@@ -32,34 +32,25 @@ use std::collections::BTreeMap;
 /// | RequestId  | String  | Unique request ID                                |
 /// | Success    | Boolean | Indicates whether the API call was successful    |
 /// | Data       | Object  | Account balance details                          |
-pub async fn query_account_balance(
-    client: &AliyunClient,
-) -> Result<Value, Box<dyn std::error::Error>> {
+pub async fn query_account_balance(client: &AliyunClient) -> Result<Value, AdvancedClientError> {
     let mut params: BTreeMap<String, String> = BTreeMap::new();
     params.insert("Action".to_string(), "QueryAccountBalance".to_string());
     params.insert("Format".to_string(), "JSON".to_string());
     // API version as per official documentation
     params.insert("Version".to_string(), "2017-12-14".to_string());
-    client.send_request("business.aliyuncs.com", params).await
+    client
+        .send_request(&client.endpoint("business", None), params)
+        .await
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
-    use crate::client::AliyunClient;
-    use crate::test_utils::TEST_SECRETS;
+    use crate::test_utils::cassette::{cassette_client, hand_written_client};
     use tokio;
 
-    #[tokio::test]
-    async fn test_query_account_balance() {
-        // Use the centralized TEST_SECRETS to build the client
-        let secrets = &*TEST_SECRETS;
-        let client = AliyunClient::new(
-            secrets.access_key_id.clone(),
-            secrets.access_key_secret.clone(),
-        );
-
-        let result = query_account_balance(&client).await;
+    async fn check_query_account_balance(client: &AliyunClient) {
+        let result = query_account_balance(client).await;
         match &result {
             Ok(json) => println!("Billing module - Query Account Balance success:\n{}", json),
             Err(err) => eprintln!("Billing module - Query Account Balance error:\n{}", err),
@@ -71,4 +62,17 @@ mod tests {
             result.err()
         );
     }
+
+    // Needs tests/cassettes/billing_query_account_balance.json, recorded by running it with
+    // ALIYUN_CASSETTE_MODE=record and the test secrets.
+    #[tokio::test]
+    #[ignore]
+    async fn test_query_account_balance() {
+        check_query_account_balance(&cassette_client("billing_query_account_balance")).await;
+    }
+
+    #[tokio::test]
+    async fn test_query_account_balance_with_hand_written_fixture() {
+        check_query_account_balance(&hand_written_client("billing_query_account_balance")).await;
+    }
 }
@@ -1,12 +1,16 @@
-use crate::client::AliyunClient;
+use crate::client::{
+    ecs::requests::{DescribeInstancesRequest, RunInstancesRequest},
+    error::AdvancedClientError,
+    AliyunClient,
+};
 use serde_json::Value;
 use std::collections::BTreeMap;
-use std::error::Error;
+use uuid::Uuid;
 
 /// Describe Regions - 查询地域列表
 ///
 /// **API Description:**
-/// - Request Domain: ecs.aliyuncs.com
+/// - Request Domain: ecs.{RegionId}.aliyuncs.com
 /// - API Version: 2014-05-26
 /// - Description: This API queries the list of available regions for ECS.
 ///
@@ -26,7 +30,7 @@ use std::error::Error;
 pub async fn describe_regions(
     client: &AliyunClient,
     region_id: Option<&str>,
-) -> Result<Value, Box<dyn Error>> {
+) -> Result<Value, AdvancedClientError> {
     let mut params = BTreeMap::new();
     params.insert("Action".to_string(), "DescribeRegions".to_string());
     params.insert("Format".to_string(), "JSON".to_string());
@@ -34,13 +38,15 @@ pub async fn describe_regions(
     if let Some(r) = region_id {
         params.insert("RegionId".to_string(), r.to_string());
     }
-    client.send_request("ecs.aliyuncs.com", params).await
+    client
+        .send_request(&client.endpoint("ecs", region_id), params)
+        .await
 }
 
 /// Describe Zones - 查询可用区列表
 ///
 /// **API Description:**
-/// - Request Domain: ecs.aliyuncs.com
+/// - Request Domain: ecs.{RegionId}.aliyuncs.com
 /// - API Version: 2014-05-26
 /// - Description: This API queries the list of available zones within a specified region.
 ///
@@ -60,19 +66,21 @@ pub async fn describe_regions(
 pub async fn describe_zones(
     client: &AliyunClient,
     region_id: &str,
-) -> Result<Value, Box<dyn Error>> {
+) -> Result<Value, AdvancedClientError> {
     let mut params = BTreeMap::new();
     params.insert("Action".to_string(), "DescribeZones".to_string());
     params.insert("Format".to_string(), "JSON".to_string());
     params.insert("Version".to_string(), "2014-05-26".to_string());
     params.insert("RegionId".to_string(), region_id.to_string());
-    client.send_request("ecs.aliyuncs.com", params).await
+    client
+        .send_request(&client.endpoint("ecs", Some(region_id)), params)
+        .await
 }
 
 /// Describe Available Resource - 查询可用区的资源库存
 ///
 /// **API Description:**
-/// - Request Domain: ecs.aliyuncs.com
+/// - Request Domain: ecs.{RegionId}.aliyuncs.com
 /// - API Version: 2014-05-26
 /// - Description: This API queries the resource inventory in a specified zone.
 ///
@@ -94,7 +102,7 @@ pub async fn describe_available_resource(
     client: &AliyunClient,
     region_id: &str,
     zone_id: &str,
-) -> Result<Value, Box<dyn Error>> {
+) -> Result<Value, AdvancedClientError> {
     let mut params = BTreeMap::new();
     params.insert(
         "Action".to_string(),
@@ -104,13 +112,15 @@ pub async fn describe_available_resource(
     params.insert("Version".to_string(), "2014-05-26".to_string());
     params.insert("RegionId".to_string(), region_id.to_string());
     params.insert("ZoneId".to_string(), zone_id.to_string());
-    client.send_request("ecs.aliyuncs.com", params).await
+    client
+        .send_request(&client.endpoint("ecs", Some(region_id)), params)
+        .await
 }
 
 /// Describe Account Attributes - 查询资源配额
 ///
 /// **API Description:**
-/// - Request Domain: ecs.aliyuncs.com
+/// - Request Domain: ecs.{RegionId}.aliyuncs.com
 /// - API Version: 2014-05-26
 /// - Description: This API queries the ECS resource quotas for the account.
 ///
@@ -120,13 +130,16 @@ pub async fn describe_available_resource(
 /// | Action    | String  | Fixed value "DescribeAccountAttributes" |
 /// | Format    | String  | Fixed value "JSON"                   |
 /// | Version   | String  | Fixed value "2014-05-26"             |
+/// | RegionId  | String  | The default region of the client     |
 ///
 /// **Output Parameters:**
 /// | Field                | Type    | Description                |
 /// |----------------------|---------|----------------------------|
 /// | AccountAttributes    | Object  | ECS resource quota details |
 /// | RequestId            | String  | Unique request ID          |
-pub async fn describe_account_attributes(client: &AliyunClient) -> Result<Value, Box<dyn Error>> {
+pub async fn describe_account_attributes(
+    client: &AliyunClient,
+) -> Result<Value, AdvancedClientError> {
     let mut params = BTreeMap::new();
     params.insert(
         "Action".to_string(),
@@ -134,13 +147,18 @@ pub async fn describe_account_attributes(client: &AliyunClient) -> Result<Value,
     );
     params.insert("Format".to_string(), "JSON".to_string());
     params.insert("Version".to_string(), "2014-05-26".to_string());
-    client.send_request("ecs.aliyuncs.com", params).await
+    if let Some(region_id) = client.default_region() {
+        params.insert("RegionId".to_string(), region_id.to_string());
+    }
+    client
+        .send_request(&client.endpoint("ecs", None), params)
+        .await
 }
 
 /// Describe Resources Modification - 查询实例规格变更信息
 ///
 /// **API Description:**
-/// - Request Domain: ecs.aliyuncs.com
+/// - Request Domain: ecs.{RegionId}.aliyuncs.com
 /// - API Version: 2014-05-26
 /// - Description: Before changing instance type or system disk type, this API queries the available modification options in a zone.
 ///
@@ -162,7 +180,7 @@ pub async fn describe_resources_modification(
     client: &AliyunClient,
     region_id: &str,
     zone_id: &str,
-) -> Result<Value, Box<dyn Error>> {
+) -> Result<Value, AdvancedClientError> {
     let mut params = BTreeMap::new();
     params.insert(
         "Action".to_string(),
@@ -172,13 +190,15 @@ pub async fn describe_resources_modification(
     params.insert("Version".to_string(), "2014-05-26".to_string());
     params.insert("RegionId".to_string(), region_id.to_string());
     params.insert("ZoneId".to_string(), zone_id.to_string());
-    client.send_request("ecs.aliyuncs.com", params).await
+    client
+        .send_request(&client.endpoint("ecs", Some(region_id)), params)
+        .await
 }
 
 /// Describe Recommend Instance Type - 查询推荐实例规格
 ///
 /// **API Description:**
-/// - Request Domain: ecs.aliyuncs.com
+/// - Request Domain: ecs.{RegionId}.aliyuncs.com
 /// - API Version: 2014-05-26
 /// - Description: This API queries recommended instance types for pricing inquiries or usage recommendations.
 ///
@@ -198,7 +218,7 @@ pub async fn describe_resources_modification(
 pub async fn describe_recommend_instance_type(
     client: &AliyunClient,
     region_id: &str,
-) -> Result<Value, Box<dyn Error>> {
+) -> Result<Value, AdvancedClientError> {
     let mut params = BTreeMap::new();
     params.insert(
         "Action".to_string(),
@@ -207,13 +227,15 @@ pub async fn describe_recommend_instance_type(
     params.insert("Format".to_string(), "JSON".to_string());
     params.insert("Version".to_string(), "2014-05-26".to_string());
     params.insert("RegionId".to_string(), region_id.to_string());
-    client.send_request("ecs.aliyuncs.com", params).await
+    client
+        .send_request(&client.endpoint("ecs", Some(region_id)), params)
+        .await
 }
 
 /// Run Instances - 批量创建实例
 ///
 /// **API Description:**
-/// - Request Domain: ecs.aliyuncs.com
+/// - Request Domain: ecs.{RegionId}.aliyuncs.com
 /// - API Version: 2014-05-26
 /// - Description: This API is used to create one or more ECS instances.
 ///
@@ -226,34 +248,40 @@ pub async fn describe_recommend_instance_type(
 /// | RegionId      | String  | Required, region ID                            |
 /// | ImageId       | String  | Required, image ID                             |
 /// | InstanceType  | String  | Required, instance type                        |
-/// | ...           | ...     | Other parameters as needed                     |
+/// | VSwitchId, SecurityGroupId(s), SystemDisk.*, DataDisk.N.*, ... | String | Optional, see `RunInstancesRequest` |
+/// | UserData      | String  | Optional, base64-encoded by `RunInstancesRequest` |
+/// | Tag.N.Key     | String  | Optional, tags numbered from 1                 |
+/// | ClientToken   | String  | Generated unless set, makes retries of the request idempotent |
+/// | DryRun        | Boolean | Optional, only checks the request              |
 ///
 /// **Output Parameters:**
-/// | Field        | Type   | Description                                    |
-/// |--------------|--------|------------------------------------------------|
-/// | InstanceIds  | Array  | List of created instance IDs                   |
-/// | RequestId    | String | Unique request ID                              |
+/// | Field          | Type   | Description                                    |
+/// |----------------|--------|------------------------------------------------|
+/// | InstanceIdSets | Array  | List of created instance IDs                   |
+/// | RequestId      | String | Unique request ID                              |
 pub async fn run_instances(
     client: &AliyunClient,
     region_id: &str,
-    image_id: &str,
-    instance_type: &str,
-) -> Result<Value, Box<dyn Error>> {
+    request: &RunInstancesRequest,
+) -> Result<Value, AdvancedClientError> {
     let mut params = BTreeMap::new();
     params.insert("Action".to_string(), "RunInstances".to_string());
     params.insert("Format".to_string(), "JSON".to_string());
     params.insert("Version".to_string(), "2014-05-26".to_string());
     params.insert("RegionId".to_string(), region_id.to_string());
-    params.insert("ImageId".to_string(), image_id.to_string());
-    params.insert("InstanceType".to_string(), instance_type.to_string());
-    // Add other required parameters as needed.
-    client.send_request("ecs.aliyuncs.com", params).await
+    request.insert_params(&mut params);
+    params
+        .entry("ClientToken".to_string())
+        .or_insert_with(|| Uuid::new_v4().to_string());
+    client
+        .send_request(&client.endpoint("ecs", Some(region_id)), params)
+        .await
 }
 
 /// Start Instances - 启动实例
 ///
 /// **API Description:**
-/// - Request Domain: ecs.aliyuncs.com
+/// - Request Domain: ecs.{RegionId}.aliyuncs.com
 /// - API Version: 2014-05-26
 /// - Description: This API is used to start one or more stopped ECS instances.
 ///
@@ -264,6 +292,7 @@ pub async fn run_instances(
 /// | Format        | String  | Fixed value "JSON"                             |
 /// | Version       | String  | Fixed value "2014-05-26"                       |
 /// | InstanceIds   | String  | Required, list of instance IDs in JSON array format |
+/// | RegionId      | String  | The default region of the client               |
 ///
 /// **Output Parameters:**
 /// | Field      | Type   | Description                                    |
@@ -272,7 +301,16 @@ pub async fn run_instances(
 pub async fn start_instances(
     client: &AliyunClient,
     instance_ids: Vec<&str>,
-) -> Result<Value, Box<dyn Error>> {
+) -> Result<Value, AdvancedClientError> {
+    start_instances_with_dry_run(client, instance_ids, None).await
+}
+
+/// `start_instances` with the `DryRun` parameter, see `EcsClient::dry_run_start_instances`.
+pub(crate) async fn start_instances_with_dry_run(
+    client: &AliyunClient,
+    instance_ids: Vec<&str>,
+    dry_run: Option<bool>,
+) -> Result<Value, AdvancedClientError> {
     let mut params = BTreeMap::new();
     params.insert("Action".to_string(), "StartInstances".to_string());
     params.insert("Format".to_string(), "JSON".to_string());
@@ -286,13 +324,21 @@ pub async fn start_instances(
             .join(",")
     );
     params.insert("InstanceIds".to_string(), ids);
-    client.send_request("ecs.aliyuncs.com", params).await
+    if let Some(region_id) = client.default_region() {
+        params.insert("RegionId".to_string(), region_id.to_string());
+    }
+    if let Some(dr) = dry_run {
+        params.insert("DryRun".to_string(), dr.to_string());
+    }
+    client
+        .send_request(&client.endpoint("ecs", None), params)
+        .await
 }
 
 /// Stop Instances - 停止实例
 ///
 /// **API Description:**
-/// - Request Domain: ecs.aliyuncs.com
+/// - Request Domain: ecs.{RegionId}.aliyuncs.com
 /// - API Version: 2014-05-26
 /// - Description: This API is used to stop running ECS instances.
 ///
@@ -303,6 +349,7 @@ pub async fn start_instances(
 /// | Format        | String  | Fixed value "JSON"                             |
 /// | Version       | String  | Fixed value "2014-05-26"                       |
 /// | InstanceIds   | String  | Required, list of instance IDs in JSON array format |
+/// | RegionId      | String  | The default region of the client               |
 /// | ForceStop     | Boolean | Optional, whether to force stop, default false |
 /// | DryRun        | Boolean | Optional, whether to perform a dry run         |
 ///
@@ -315,7 +362,7 @@ pub async fn stop_instances(
     instance_ids: Vec<&str>,
     force_stop: Option<bool>,
     dry_run: Option<bool>,
-) -> Result<Value, Box<dyn Error>> {
+) -> Result<Value, AdvancedClientError> {
     let mut params = BTreeMap::new();
     params.insert("Action".to_string(), "StopInstances".to_string());
     params.insert("Format".to_string(), "JSON".to_string());
@@ -329,19 +376,24 @@ pub async fn stop_instances(
             .join(",")
     );
     params.insert("InstanceIds".to_string(), ids);
+    if let Some(region_id) = client.default_region() {
+        params.insert("RegionId".to_string(), region_id.to_string());
+    }
     if let Some(fs) = force_stop {
         params.insert("ForceStop".to_string(), fs.to_string());
     }
     if let Some(dr) = dry_run {
         params.insert("DryRun".to_string(), dr.to_string());
     }
-    client.send_request("ecs.aliyuncs.com", params).await
+    client
+        .send_request(&client.endpoint("ecs", None), params)
+        .await
 }
 
 /// Reboot Instance - 重启实例
 ///
 /// **API Description:**
-/// - Request Domain: ecs.aliyuncs.com
+/// - Request Domain: ecs.{RegionId}.aliyuncs.com
 /// - API Version: 2014-05-26
 /// - Description: This API is used to reboot a running ECS instance. It is asynchronous; after calling,
 ///   the instance status will first become "Starting", and when it becomes "Running", the reboot is successful.
@@ -365,7 +417,7 @@ pub async fn reboot_instance(
     instance_id: &str,
     force_stop: Option<bool>,
     dry_run: Option<bool>,
-) -> Result<Value, Box<dyn Error>> {
+) -> Result<Value, AdvancedClientError> {
     let mut params = BTreeMap::new();
     params.insert("Action".to_string(), "RebootInstance".to_string());
     params.insert("Format".to_string(), "JSON".to_string());
@@ -377,13 +429,15 @@ pub async fn reboot_instance(
     if let Some(dr) = dry_run {
         params.insert("DryRun".to_string(), dr.to_string());
     }
-    client.send_request("ecs.aliyuncs.com", params).await
+    client
+        .send_request(&client.endpoint("ecs", None), params)
+        .await
 }
 
 /// Delete Instance - 删除实例
 ///
 /// **API Description:**
-/// - Request Domain: ecs.aliyuncs.com
+/// - Request Domain: ecs.{RegionId}.aliyuncs.com
 /// - API Version: 2014-05-26
 /// - Description: This API is used to delete a specified ECS instance. Once deleted, the instance cannot be recovered.
 ///
@@ -402,19 +456,33 @@ pub async fn reboot_instance(
 pub async fn delete_instance(
     client: &AliyunClient,
     instance_id: &str,
-) -> Result<Value, Box<dyn Error>> {
+) -> Result<Value, AdvancedClientError> {
+    delete_instance_with_dry_run(client, instance_id, None).await
+}
+
+/// `delete_instance` with the `DryRun` parameter, see `EcsClient::dry_run_delete_instance`.
+pub(crate) async fn delete_instance_with_dry_run(
+    client: &AliyunClient,
+    instance_id: &str,
+    dry_run: Option<bool>,
+) -> Result<Value, AdvancedClientError> {
     let mut params = BTreeMap::new();
     params.insert("Action".to_string(), "DeleteInstance".to_string());
     params.insert("Format".to_string(), "JSON".to_string());
     params.insert("Version".to_string(), "2014-05-26".to_string());
     params.insert("InstanceId".to_string(), instance_id.to_string());
-    client.send_request("ecs.aliyuncs.com", params).await
+    if let Some(dr) = dry_run {
+        params.insert("DryRun".to_string(), dr.to_string());
+    }
+    client
+        .send_request(&client.endpoint("ecs", None), params)
+        .await
 }
 
 /// Describe Instance Status - 查询实例状态信息列表
 ///
 /// **API Description:**
-/// - Request Domain: ecs.aliyuncs.com
+/// - Request Domain: ecs.{RegionId}.aliyuncs.com
 /// - API Version: 2014-05-26
 /// - Description: This API queries the status information of one or more ECS instances.
 ///
@@ -440,7 +508,7 @@ pub async fn describe_instance_status(
     instance_id: Option<&str>,
     page_number: Option<u32>,
     page_size: Option<u32>,
-) -> Result<Value, Box<dyn Error>> {
+) -> Result<Value, AdvancedClientError> {
     let mut params = BTreeMap::new();
     params.insert("Action".to_string(), "DescribeInstanceStatus".to_string());
     params.insert("Format".to_string(), "JSON".to_string());
@@ -455,13 +523,55 @@ pub async fn describe_instance_status(
     if let Some(size) = page_size {
         params.insert("PageSize".to_string(), size.to_string());
     }
-    client.send_request("ecs.aliyuncs.com", params).await
+    client
+        .send_request(&client.endpoint("ecs", Some(region_id)), params)
+        .await
+}
+
+/// Describe Instance Status by IDs - 查询指定实例的状态
+///
+/// **API Description:**
+/// - Request Domain: ecs.{RegionId}.aliyuncs.com
+/// - API Version: 2014-05-26
+/// - Description: Queries the status of the given instances (at most 100) in one request.
+///
+/// **Input Parameters:**
+/// | Parameter     | Type     | Description                                        |
+/// |---------------|----------|----------------------------------------------------|
+/// | Action        | String   | Fixed value "DescribeInstanceStatus"               |
+/// | Format        | String   | Fixed value "JSON"                                 |
+/// | Version       | String   | Fixed value "2014-05-26"                           |
+/// | RegionId      | String   | Required, the region ID                            |
+/// | InstanceId.N  | String   | Required, the instance IDs (N from 1 to 100)       |
+/// | PageSize      | Integer  | The number of instance IDs, so that one page lists them all |
+///
+/// **Output Parameters:** the same as `describe_instance_status`.
+pub async fn describe_instance_status_by_ids(
+    client: &AliyunClient,
+    region_id: &str,
+    instance_ids: &[&str],
+) -> Result<Value, AdvancedClientError> {
+    let mut params = BTreeMap::new();
+    params.insert("Action".to_string(), "DescribeInstanceStatus".to_string());
+    params.insert("Format".to_string(), "JSON".to_string());
+    params.insert("Version".to_string(), "2014-05-26".to_string());
+    params.insert("RegionId".to_string(), region_id.to_string());
+    for (i, id) in instance_ids.iter().enumerate() {
+        params.insert(format!("InstanceId.{}", i + 1), id.to_string());
+    }
+    params.insert(
+        "PageSize".to_string(),
+        instance_ids.len().clamp(1, 100).to_string(),
+    );
+    client
+        .send_request(&client.endpoint("ecs", Some(region_id)), params)
+        .await
 }
 
 /// Describe Instances - 查询实例详细信息列表
 ///
 /// **API Description:**
-/// - Request Domain: ecs.aliyuncs.com
+/// - Request Domain: ecs.{RegionId}.aliyuncs.com
 /// - API Version: 2014-05-26
 /// - Description: This API queries detailed information of ECS instances with filtering support.
 ///
@@ -472,9 +582,12 @@ pub async fn describe_instance_status(
 /// | Format        | String  | Fixed value "JSON"                                 |
 /// | Version       | String  | Fixed value "2014-05-26"                           |
 /// | RegionId      | String  | Required, the region ID                            |
-/// | Filters       | String  | Optional, filtering conditions in JSON string format |
+/// | InstanceIds   | String  | Optional, instance IDs as a JSON array             |
+/// | VpcId, VSwitchId, ZoneId, Status, InstanceName, ... | String | Optional filters, see `DescribeInstancesRequest` |
+/// | Tag.N.Key     | String  | Optional, tag filters numbered from 1              |
 /// | PageNumber    | Integer | Optional, page number                              |
 /// | PageSize      | Integer | Optional, page size                                |
+/// | NextToken     | String  | Optional, token of the page, with MaxResults       |
 ///
 /// **Output Parameters:**
 /// | Field      | Type    | Description                                    |
@@ -485,47 +598,84 @@ pub async fn describe_instance_status(
 pub async fn describe_instances(
     client: &AliyunClient,
     region_id: &str,
-    filters: Option<&str>,
-    page_number: Option<u32>,
-    page_size: Option<u32>,
-) -> Result<Value, Box<dyn Error>> {
+    request: &DescribeInstancesRequest,
+) -> Result<Value, AdvancedClientError> {
     let mut params = BTreeMap::new();
     params.insert("Action".to_string(), "DescribeInstances".to_string());
     params.insert("Format".to_string(), "JSON".to_string());
     params.insert("Version".to_string(), "2014-05-26".to_string());
     params.insert("RegionId".to_string(), region_id.to_string());
-    if let Some(f) = filters {
-        params.insert("Filters".to_string(), f.to_string());
-    }
-    if let Some(page) = page_number {
-        params.insert("PageNumber".to_string(), page.to_string());
-    }
-    if let Some(size) = page_size {
-        params.insert("PageSize".to_string(), size.to_string());
-    }
-    client.send_request("ecs.aliyuncs.com", params).await
+    request.insert_params(&mut params);
+    client
+        .send_request(&client.endpoint("ecs", Some(region_id)), params)
+        .await
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::client::ecs::regions::DescribeRegionsBody;
+    use crate::client::transport::{HttpResponse, MockTransport};
+    use crate::client::utils::parse_json_value;
     use crate::client::AliyunClient;
-    use tokio;
+    use crate::test_utils::cassette::{cassette_client, hand_written_client};
     use crate::test_utils::TEST_SECRETS;
+    use tokio;
 
     const TEST_REGION: &str = "cn-hangzhou";
     const TEST_ZONE: &str = "cn-hangzhou-d";
     const TEST_INSTANCE_ID: &str = "YourInstanceId";
 
+    async fn check_describe_regions(client: &AliyunClient) {
+        let result = describe_regions(client, Some(TEST_REGION)).await.unwrap();
+        let body = parse_json_value::<DescribeRegionsBody>(result).unwrap();
+        assert!(!body.request_id.is_empty());
+        let region = body
+            .regions
+            .iter()
+            .find(|region| region.region_id == TEST_REGION)
+            .unwrap();
+        assert_eq!(
+            region.region_endpoint.as_deref(),
+            Some("ecs.cn-hangzhou.aliyuncs.com")
+        );
+    }
+
+    // Needs tests/cassettes/ecs_describe_regions.json, recorded by running it with
+    // ALIYUN_CASSETTE_MODE=record and the test secrets.
     #[tokio::test]
+    #[ignore]
     async fn test_describe_regions() {
-        let client = AliyunClient::new(
-            TEST_SECRETS.access_key_id.clone(),
-            TEST_SECRETS.access_key_secret.clone(),
-        );
-        let result = describe_regions(&client, Some(TEST_REGION)).await;
-        println!("describe_regions: {:?}", result);
-        assert!(result.is_ok());
+        check_describe_regions(&cassette_client("ecs_describe_regions")).await;
+    }
+
+    #[tokio::test]
+    async fn test_describe_regions_with_hand_written_fixture() {
+        check_describe_regions(&hand_written_client("ecs_describe_regions")).await;
+    }
+
+    #[tokio::test]
+    async fn test_describe_regions_with_mock_transport() {
+        let transport = MockTransport::new();
+        transport.push_response(HttpResponse::json(&serde_json::json!({
+            "RequestId": "REQ",
+            "Regions": {"Region": [{"RegionId": TEST_REGION, "LocalName": "华东1（杭州）"}]}
+        })));
+        let client = AliyunClient::builder()
+            .credentials("testid".to_string(), "testsecret".to_string())
+            .transport(transport.clone())
+            .build()
+            .unwrap();
+
+        let result = describe_regions(&client, Some(TEST_REGION)).await.unwrap();
+        assert_eq!(result["Regions"]["Region"][0]["RegionId"], TEST_REGION);
+        let request = &transport.requests()[0];
+        assert!(request
+            .url
+            .starts_with("https://ecs.cn-hangzhou.aliyuncs.com?"));
+        let params = request.query_params();
+        assert_eq!(params["Action"], "DescribeRegions");
+        assert_eq!(params["RegionId"], TEST_REGION);
     }
 
     #[tokio::test]
@@ -597,9 +747,8 @@ mod tests {
             TEST_SECRETS.access_key_id.clone(),
             TEST_SECRETS.access_key_secret.clone(),
         );
-        let image_id = "YourImageId";
-        let instance_type = "ecs.g5.large";
-        let result = run_instances(&client, TEST_REGION, image_id, instance_type).await;
+        let request = RunInstancesRequest::new("YourImageId", "ecs.g5.large");
+        let result = run_instances(&client, TEST_REGION, &request).await;
         println!("run_instances: {:?}", result);
         // assert!(result.is_ok());
     }
@@ -671,7 +820,12 @@ mod tests {
             TEST_SECRETS.access_key_id.clone(),
             TEST_SECRETS.access_key_secret.clone(),
         );
-        let result = describe_instances(&client, TEST_REGION, None, Some(1), Some(10)).await;
+        let result = describe_instances(
+            &client,
+            TEST_REGION,
+            &DescribeInstancesRequest::new().page_number(1).page_size(10),
+        )
+        .await;
         println!("describe_instances: {:?}", result);
         assert!(result.is_ok());
     }
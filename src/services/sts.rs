@@ -1,6 +1,6 @@
-use crate::client::AliyunClient;
+use crate::client::{error::AdvancedClientError, AliyunClient};
 use serde_json::Value;
-use std::{collections::BTreeMap, error::Error};
+use std::collections::BTreeMap;
 
 /// Get Caller Identity - GetCallerIdentity
 ///
@@ -8,7 +8,7 @@ use std::{collections::BTreeMap, error::Error};
 /// - Request Domain: sts.aliyuncs.com
 /// - API Version: 2015-04-01
 /// - Description: This API returns information about the caller's identity.
-/// - Aliyun Document Link: https://help.aliyun.com/zh/ram/developer-reference/api-sts-2015-04-01-getcalleridentity
+/// - Aliyun Document Link: <https://help.aliyun.com/zh/ram/developer-reference/api-sts-2015-04-01-getcalleridentity>
 ///
 /// **Input Parameters:**
 ///
@@ -35,25 +35,192 @@ use std::{collections::BTreeMap, error::Error};
 /// | UserId      | String | The user ID                                                      |
 /// | Arn         | String | The ARN of the caller                                            |
 /// | RoleId      | String | The role id; returned only when the current caller is a RAM role |
-pub async fn get_caller_identity(client: &AliyunClient) -> Result<Value, Box<dyn Error>> {
+pub async fn get_caller_identity(client: &AliyunClient) -> Result<Value, AdvancedClientError> {
     let mut params = BTreeMap::new();
     // todo: abstract `to_string`
     params.insert("Action".to_string(), "GetCallerIdentity".to_string());
     params.insert("Format".to_string(), "JSON".to_string());
     params.insert("Version".to_string(), "2015-04-01".to_string());
-    client.send_request("sts.aliyuncs.com", params).await
+    client
+        .send_request(&client.endpoint("sts", None), params)
+        .await
+}
+
+/// Assume Role - AssumeRole
+///
+/// **API Description:**
+/// - Request Domain: sts.aliyuncs.com
+/// - API Version: 2015-04-01
+/// - Description: This API obtains temporary credentials (STS token) for assuming a RAM role.
+/// - Aliyun Document Link: <https://help.aliyun.com/zh/ram/developer-reference/api-sts-2015-04-01-assumerole>
+///
+/// **Input Parameters:**
+///
+/// | Parameter        | Type    | Description                                                   |
+/// |------------------|---------|---------------------------------------------------------------|
+/// | Action           | String  | Fixed value: "AssumeRole"                                     |
+/// | Format           | String  | Fixed value: "JSON"                                           |
+/// | Version          | String  | Fixed value: "2015-04-01"                                     |
+/// | RoleArn          | String  | Required, the ARN of the RAM role to assume                   |
+/// | RoleSessionName  | String  | Required, a custom name identifying the session               |
+/// | DurationSeconds  | Long    | Optional, validity period in seconds (900 to the role's max)  |
+/// | Policy           | String  | Optional, a policy further restricting the STS token          |
+///
+/// **Output Parameters:**
+///
+/// | Field           | Type   | Description                                                      |
+/// |-----------------|--------|------------------------------------------------------------------|
+/// | RequestId       | String | Unique request ID                                                |
+/// | Credentials     | Object | AccessKeyId, AccessKeySecret, SecurityToken and Expiration       |
+/// | AssumedRoleUser | Object | AssumedRoleId and Arn of the temporary identity                  |
+pub async fn assume_role(
+    client: &AliyunClient,
+    role_arn: &str,
+    role_session_name: &str,
+    duration_seconds: Option<u64>,
+    policy: Option<&str>,
+) -> Result<Value, AdvancedClientError> {
+    let mut params = BTreeMap::new();
+    params.insert("Action".to_string(), "AssumeRole".to_string());
+    params.insert("Format".to_string(), "JSON".to_string());
+    params.insert("Version".to_string(), "2015-04-01".to_string());
+    params.insert("RoleArn".to_string(), role_arn.to_string());
+    params.insert("RoleSessionName".to_string(), role_session_name.to_string());
+    if let Some(duration) = duration_seconds {
+        params.insert("DurationSeconds".to_string(), duration.to_string());
+    }
+    if let Some(p) = policy {
+        params.insert("Policy".to_string(), p.to_string());
+    }
+    client
+        .send_request(&client.endpoint("sts", None), params)
+        .await
+}
+
+/// Assume Role With SAML - AssumeRoleWithSAML
+///
+/// **API Description:**
+/// - Request Domain: sts.aliyuncs.com
+/// - API Version: 2015-04-01
+/// - Description: This API obtains temporary credentials for a RAM role with a SAML assertion
+///   issued by the identity provider of a role-based SSO. It is an anonymous operation.
+/// - Aliyun Document Link: <https://help.aliyun.com/zh/ram/developer-reference/api-sts-2015-04-01-assumerolewithsaml>
+///
+/// **Input Parameters:**
+///
+/// | Parameter        | Type    | Description                                                   |
+/// |------------------|---------|---------------------------------------------------------------|
+/// | Action           | String  | Fixed value: "AssumeRoleWithSAML"                             |
+/// | Format           | String  | Fixed value: "JSON"                                           |
+/// | Version          | String  | Fixed value: "2015-04-01"                                     |
+/// | SAMLProviderArn  | String  | Required, the ARN of the SAML identity provider               |
+/// | RoleArn          | String  | Required, the ARN of the RAM role to assume                   |
+/// | SAMLAssertion    | String  | Required, the Base64 encoded SAML assertion                   |
+/// | DurationSeconds  | Long    | Optional, validity period in seconds (900 to the role's max)  |
+/// | Policy           | String  | Optional, a policy further restricting the STS token          |
+///
+/// **Output Parameters:**
+///
+/// | Field             | Type   | Description                                                      |
+/// |-------------------|--------|------------------------------------------------------------------|
+/// | RequestId         | String | Unique request ID                                                |
+/// | Credentials       | Object | AccessKeyId, AccessKeySecret, SecurityToken and Expiration       |
+/// | AssumedRoleUser   | Object | AssumedRoleId and Arn of the temporary identity                  |
+/// | SAMLAssertionInfo | Object | Subject, SubjectType, Issuer and Recipient of the assertion      |
+pub async fn assume_role_with_saml(
+    client: &AliyunClient,
+    saml_provider_arn: &str,
+    role_arn: &str,
+    saml_assertion: &str,
+    duration_seconds: Option<u64>,
+    policy: Option<&str>,
+) -> Result<Value, AdvancedClientError> {
+    let mut params = BTreeMap::new();
+    params.insert("Action".to_string(), "AssumeRoleWithSAML".to_string());
+    params.insert("Format".to_string(), "JSON".to_string());
+    params.insert("Version".to_string(), "2015-04-01".to_string());
+    params.insert("SAMLProviderArn".to_string(), saml_provider_arn.to_string());
+    params.insert("RoleArn".to_string(), role_arn.to_string());
+    params.insert("SAMLAssertion".to_string(), saml_assertion.to_string());
+    if let Some(duration) = duration_seconds {
+        params.insert("DurationSeconds".to_string(), duration.to_string());
+    }
+    if let Some(p) = policy {
+        params.insert("Policy".to_string(), p.to_string());
+    }
+    client
+        .send_anonymous_request(&client.endpoint("sts", None), params)
+        .await
+}
+
+/// Assume Role With OIDC - AssumeRoleWithOIDC
+///
+/// **API Description:**
+/// - Request Domain: sts.aliyuncs.com
+/// - API Version: 2015-04-01
+/// - Description: This API obtains temporary credentials for a RAM role with an OIDC token,
+///   e.g. the service account token projected by RRSA. It is an anonymous operation.
+/// - Aliyun Document Link: <https://help.aliyun.com/zh/ram/developer-reference/api-sts-2015-04-01-assumerolewithoidc>
+///
+/// **Input Parameters:**
+///
+/// | Parameter        | Type    | Description                                                   |
+/// |------------------|---------|---------------------------------------------------------------|
+/// | Action           | String  | Fixed value: "AssumeRoleWithOIDC"                             |
+/// | Format           | String  | Fixed value: "JSON"                                           |
+/// | Version          | String  | Fixed value: "2015-04-01"                                     |
+/// | OIDCProviderArn  | String  | Required, the ARN of the OIDC identity provider               |
+/// | RoleArn          | String  | Required, the ARN of the RAM role to assume                   |
+/// | OIDCToken        | String  | Required, the OIDC token issued by the identity provider      |
+/// | RoleSessionName  | String  | Optional, a custom name identifying the session               |
+/// | DurationSeconds  | Long    | Optional, validity period in seconds (900 to the role's max)  |
+/// | Policy           | String  | Optional, a policy further restricting the STS token          |
+///
+/// **Output Parameters:**
+///
+/// | Field           | Type   | Description                                                      |
+/// |-----------------|--------|------------------------------------------------------------------|
+/// | RequestId       | String | Unique request ID                                                |
+/// | Credentials     | Object | AccessKeyId, AccessKeySecret, SecurityToken and Expiration       |
+/// | AssumedRoleUser | Object | AssumedRoleId and Arn of the temporary identity                  |
+/// | OIDCTokenInfo   | Object | Subject, Issuer, ClientIds and validity of the OIDC token        |
+pub async fn assume_role_with_oidc(
+    client: &AliyunClient,
+    oidc_provider_arn: &str,
+    role_arn: &str,
+    oidc_token: &str,
+    role_session_name: Option<&str>,
+    duration_seconds: Option<u64>,
+    policy: Option<&str>,
+) -> Result<Value, AdvancedClientError> {
+    let mut params = BTreeMap::new();
+    params.insert("Action".to_string(), "AssumeRoleWithOIDC".to_string());
+    params.insert("Format".to_string(), "JSON".to_string());
+    params.insert("Version".to_string(), "2015-04-01".to_string());
+    params.insert("OIDCProviderArn".to_string(), oidc_provider_arn.to_string());
+    params.insert("RoleArn".to_string(), role_arn.to_string());
+    params.insert("OIDCToken".to_string(), oidc_token.to_string());
+    if let Some(name) = role_session_name {
+        params.insert("RoleSessionName".to_string(), name.to_string());
+    }
+    if let Some(duration) = duration_seconds {
+        params.insert("DurationSeconds".to_string(), duration.to_string());
+    }
+    if let Some(p) = policy {
+        params.insert("Policy".to_string(), p.to_string());
+    }
+    client
+        .send_anonymous_request(&client.endpoint("sts", None), params)
+        .await
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
-    use crate::test_utils::{create_aliyun_client, EMPTY, GLOBAL_TEST_SECRETS};
+    use crate::test_utils::cassette::{cassette_client, hand_written_client};
 
-    #[tokio::test]
-    async fn test_get_caller_identity() {
-        let client = create_aliyun_client::<GLOBAL_TEST_SECRETS>();
-
-        let result = get_caller_identity(&client).await;
+    async fn check_get_caller_identity(client: &AliyunClient) {
+        let result = get_caller_identity(client).await;
         println!("get_caller_identity: {:?}", result);
         assert!(result.is_ok());
 
@@ -66,9 +233,18 @@ mod tests {
         assert!(response.get("Arn").is_some());
         // Returned only when the current caller is a RAM role.
         // assert!(response.get("RoleId").is_some());
+    }
 
-        let empty_credentials_client = create_aliyun_client::<EMPTY>();
-        let result = get_caller_identity(&empty_credentials_client).await;
-        println!("get_caller_identity (empty credentials): {:?}", result);
+    // Needs tests/cassettes/sts_get_caller_identity.json, recorded by running it with
+    // ALIYUN_CASSETTE_MODE=record and the test secrets.
+    #[tokio::test]
+    #[ignore]
+    async fn test_get_caller_identity() {
+        check_get_caller_identity(&cassette_client("sts_get_caller_identity")).await;
+    }
+
+    #[tokio::test]
+    async fn test_get_caller_identity_with_hand_written_fixture() {
+        check_get_caller_identity(&hand_written_client("sts_get_caller_identity")).await;
     }
 }
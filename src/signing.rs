@@ -2,9 +2,25 @@ use crate::utils;
 use base64::{engine::general_purpose, Engine as _};
 use hmac::{Hmac, Mac};
 use sha1::Sha1;
+use sha2::{Digest, Sha256};
 use std::collections::BTreeMap;
 
 pub type HmacSha1 = Hmac<Sha1>;
+pub type HmacSha256 = Hmac<Sha256>;
+
+/// The algorithm identifier of the V3 signature, used both in the string to sign
+/// and as the scheme of the `Authorization` header.
+pub const ACS3_HMAC_SHA256: &str = "ACS3-HMAC-SHA256";
+
+/// The signature scheme used to authenticate a request.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum SignatureVersion {
+    /// The legacy RPC signature: HMAC-SHA1 over `GET&%2F&<query>`, sent as the `Signature` query parameter.
+    #[default]
+    V1,
+    /// The ACS3-HMAC-SHA256 signature over a canonical request, sent in the `Authorization` header.
+    V3,
+}
 
 /// Calculates the Aliyun API signature for the given parameters.
 ///
@@ -32,5 +48,311 @@ pub fn calculate_signature(params: &BTreeMap<String, String>, access_key_secret:
     mac.update(string_to_sign.as_bytes());
     let result = mac.finalize().into_bytes();
     // Use the new Engine API for base64 encoding
-    general_purpose::STANDARD.encode(&result)
+    general_purpose::STANDARD.encode(result)
+}
+
+/// Hashes a request payload with SHA-256 and returns the lowercase hex digest,
+/// as required by the `x-acs-content-sha256` header of the V3 signature.
+pub fn hash_payload(payload: &[u8]) -> String {
+    utils::hex_encode(&Sha256::digest(payload))
+}
+
+/// Builds the V3 canonical request.
+///
+/// Only `host`, `content-type` and `x-acs-*` headers take part in the signature; header
+/// names are lowercased and values trimmed before being sorted.
+///
+/// # Arguments
+///
+/// * `method` - The HTTP method (e.g., "GET" or "POST").
+/// * `canonical_uri` - The URL-encoded request path, "/" for RPC style APIs.
+/// * `params` - The query parameters (already sorted).
+/// * `headers` - The request headers.
+/// * `hashed_payload` - The hex encoded SHA-256 of the request body.
+///
+/// # Returns
+///
+/// A tuple of the canonical request and the `;` separated list of signed headers.
+pub fn build_canonical_request(
+    method: &str,
+    canonical_uri: &str,
+    params: &BTreeMap<String, String>,
+    headers: &BTreeMap<String, String>,
+    hashed_payload: &str,
+) -> (String, String) {
+    let signed: BTreeMap<String, &str> = headers
+        .iter()
+        .map(|(k, v)| (k.to_lowercase(), v.trim()))
+        .filter(|(k, _)| k == "host" || k == "content-type" || k.starts_with("x-acs-"))
+        .collect();
+    let canonical_headers: String = signed
+        .iter()
+        .map(|(k, v)| format!("{}:{}\n", k, v))
+        .collect();
+    let signed_headers = signed.keys().cloned().collect::<Vec<String>>().join(";");
+
+    let canonical_request = format!(
+        "{}\n{}\n{}\n{}\n{}\n{}",
+        method,
+        canonical_uri,
+        utils::build_canonicalized_query(params),
+        canonical_headers,
+        signed_headers,
+        hashed_payload
+    );
+    (canonical_request, signed_headers)
+}
+
+/// Builds the V3 string to sign: the algorithm and the hex encoded SHA-256 of the
+/// canonical request, separated by a newline.
+pub fn build_string_to_sign_v3(canonical_request: &str) -> String {
+    format!(
+        "{}\n{}",
+        ACS3_HMAC_SHA256,
+        hash_payload(canonical_request.as_bytes())
+    )
+}
+
+/// Calculates the V3 (ACS3-HMAC-SHA256) signature of a canonical request.
+///
+/// # Returns
+///
+/// The calculated signature as a lowercase hex encoded string.
+pub fn calculate_signature_v3(canonical_request: &str, access_key_secret: &str) -> String {
+    let string_to_sign = build_string_to_sign_v3(canonical_request);
+    let mut mac = HmacSha256::new_from_slice(access_key_secret.as_bytes())
+        .expect("HMAC can take key of any size");
+    mac.update(string_to_sign.as_bytes());
+    utils::hex_encode(&mac.finalize().into_bytes())
+}
+
+/// Formats the value of the `Authorization` header for a V3 signed request.
+pub fn build_authorization_v3(
+    access_key_id: &str,
+    signed_headers: &str,
+    signature: &str,
+) -> String {
+    format!(
+        "{} Credential={},SignedHeaders={},Signature={}",
+        ACS3_HMAC_SHA256, access_key_id, signed_headers, signature
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const EMPTY_PAYLOAD_SHA256: &str =
+        "e3b0c44298fc1c149afbf4c8996fb92427ae41e4649b934ca495991b7852b855";
+
+    /// The worked example from Aliyun's V3 signature documentation.
+    fn documented_request() -> (BTreeMap<String, String>, BTreeMap<String, String>) {
+        let params = BTreeMap::from([
+            (
+                "ImageId".to_string(),
+                "win2019_1809_x64_dtc_zh-cn_40G_alibase_20230811.vhd".to_string(),
+            ),
+            ("RegionId".to_string(), "cn-beijing".to_string()),
+        ]);
+        let headers = BTreeMap::from([
+            (
+                "host".to_string(),
+                "ecs.cn-beijing.aliyuncs.com".to_string(),
+            ),
+            ("x-acs-action".to_string(), "RunInstances".to_string()),
+            ("x-acs-version".to_string(), "2014-05-26".to_string()),
+            ("x-acs-date".to_string(), "2023-10-26T10:22:32Z".to_string()),
+            (
+                "x-acs-signature-nonce".to_string(),
+                "3156853299f313e23d1673dc12e1703d".to_string(),
+            ),
+            (
+                "x-acs-content-sha256".to_string(),
+                EMPTY_PAYLOAD_SHA256.to_string(),
+            ),
+        ]);
+        (params, headers)
+    }
+
+    /// The worked example of Aliyun's RPC (V1) signature documentation.
+    fn documented_v1_params() -> BTreeMap<String, String> {
+        BTreeMap::from(
+            [
+                ("AccessKeyId", "testid"),
+                ("Action", "DescribeRegions"),
+                ("Format", "XML"),
+                ("SignatureMethod", "HMAC-SHA1"),
+                ("SignatureNonce", "3ee8c1b8-83d3-44af-a94f-4e0ad82fd6cf"),
+                ("SignatureVersion", "1.0"),
+                ("Timestamp", "2016-02-23T12:46:24Z"),
+                ("Version", "2014-05-26"),
+            ]
+            .map(|(k, v)| (k.to_string(), v.to_string())),
+        )
+    }
+
+    #[test]
+    fn test_string_to_sign_v1() {
+        let query = utils::build_canonicalized_query(&documented_v1_params());
+        let string_to_sign = format!(
+            "GET&{}&{}",
+            utils::aliyun_percent_encode("/"),
+            utils::aliyun_percent_encode(&query)
+        );
+        assert_eq!(
+            string_to_sign,
+            "GET&%2F&AccessKeyId%3Dtestid%26Action%3DDescribeRegions%26Format%3DXML%26SignatureMethod%3DHMAC-SHA1%26SignatureNonce%3D3ee8c1b8-83d3-44af-a94f-4e0ad82fd6cf%26SignatureVersion%3D1.0%26Timestamp%3D2016-02-23T12%253A46%253A24Z%26Version%3D2014-05-26"
+        );
+    }
+
+    #[test]
+    fn test_signature_v1() {
+        assert_eq!(
+            calculate_signature(&documented_v1_params(), "testsecret"),
+            "OLeaidS1JvxuMvnyHOwuJ+uX5qY="
+        );
+    }
+
+    #[test]
+    fn test_signature_v1_depends_on_every_parameter() {
+        let expected = calculate_signature(&documented_v1_params(), "testsecret");
+        assert_ne!(
+            calculate_signature(&documented_v1_params(), "testsecret2"),
+            expected
+        );
+        for name in documented_v1_params().keys() {
+            let mut params = documented_v1_params();
+            params.get_mut(name).unwrap().push('x');
+            assert_ne!(
+                calculate_signature(&params, "testsecret"),
+                expected,
+                "{}",
+                name
+            );
+        }
+    }
+
+    #[test]
+    fn test_signature_v1_of_encoded_values() {
+        // Values are encoded twice in the string to sign: `%20` becomes `%2520`.
+        let params = BTreeMap::from([
+            ("Action".to_string(), "DescribeInstances".to_string()),
+            ("InstanceName".to_string(), "web server*~+".to_string()),
+            ("Description".to_string(), "杭州".to_string()),
+        ]);
+        let query = utils::build_canonicalized_query(&params);
+        assert_eq!(
+            query,
+            "Action=DescribeInstances&Description=%E6%9D%AD%E5%B7%9E&InstanceName=web%20server%2A~%2B"
+        );
+        assert_eq!(
+            utils::aliyun_percent_encode(&query),
+            "Action%3DDescribeInstances%26Description%3D%25E6%259D%25AD%25E5%25B7%259E%26InstanceName%3Dweb%2520server%252A~%252B"
+        );
+    }
+
+    #[test]
+    fn test_hmac_sha1_rfc2202_case_2() {
+        let mut mac = HmacSha1::new_from_slice(b"Jefe").unwrap();
+        mac.update(b"what do ya want for nothing?");
+        assert_eq!(
+            utils::hex_encode(&mac.finalize().into_bytes()),
+            "effcdf6ae5eb2fa2d27416d5f184df9c259a7c79"
+        );
+    }
+
+    #[test]
+    fn test_hash_empty_payload() {
+        assert_eq!(hash_payload(b""), EMPTY_PAYLOAD_SHA256);
+    }
+
+    #[test]
+    fn test_hmac_sha256_rfc4231_case_2() {
+        let mut mac = HmacSha256::new_from_slice(b"Jefe").unwrap();
+        mac.update(b"what do ya want for nothing?");
+        assert_eq!(
+            utils::hex_encode(&mac.finalize().into_bytes()),
+            "5bdcc146bf60754e6a042426089575c75a003f089d2739839dec58b964ec3843"
+        );
+    }
+
+    #[test]
+    fn test_canonical_request_v3() {
+        let (params, headers) = documented_request();
+        let (canonical_request, signed_headers) =
+            build_canonical_request("POST", "/", &params, &headers, EMPTY_PAYLOAD_SHA256);
+
+        assert_eq!(
+            signed_headers,
+            "host;x-acs-action;x-acs-content-sha256;x-acs-date;x-acs-signature-nonce;x-acs-version"
+        );
+        assert_eq!(
+            canonical_request,
+            "POST\n\
+             /\n\
+             ImageId=win2019_1809_x64_dtc_zh-cn_40G_alibase_20230811.vhd&RegionId=cn-beijing\n\
+             host:ecs.cn-beijing.aliyuncs.com\n\
+             x-acs-action:RunInstances\n\
+             x-acs-content-sha256:e3b0c44298fc1c149afbf4c8996fb92427ae41e4649b934ca495991b7852b855\n\
+             x-acs-date:2023-10-26T10:22:32Z\n\
+             x-acs-signature-nonce:3156853299f313e23d1673dc12e1703d\n\
+             x-acs-version:2014-05-26\n\
+             \n\
+             host;x-acs-action;x-acs-content-sha256;x-acs-date;x-acs-signature-nonce;x-acs-version\n\
+             e3b0c44298fc1c149afbf4c8996fb92427ae41e4649b934ca495991b7852b855"
+        );
+    }
+
+    #[test]
+    fn test_canonical_request_ignores_unsigned_headers() {
+        let (params, mut headers) = documented_request();
+        headers.insert("User-Agent".to_string(), "alibabacloud-rust".to_string());
+        headers.insert(
+            "X-Acs-Date".to_string(),
+            " 2023-10-26T10:22:32Z ".to_string(),
+        );
+        headers.remove("x-acs-date");
+
+        let (expected, _) = build_canonical_request(
+            "POST",
+            "/",
+            &documented_request().0,
+            &documented_request().1,
+            EMPTY_PAYLOAD_SHA256,
+        );
+        let (canonical_request, _) =
+            build_canonical_request("POST", "/", &params, &headers, EMPTY_PAYLOAD_SHA256);
+        assert_eq!(canonical_request, expected);
+    }
+
+    #[test]
+    fn test_signature_v3() {
+        // The canonical request of the documented example is asserted literally by
+        // `test_canonical_request_v3`; SHA-256 and HMAC-SHA256 are checked against their
+        // published vectors by `test_hash_empty_payload` and `test_hmac_sha256_rfc4231_case_2`.
+        // The digests below were computed from that canonical request with Python's hashlib
+        // and hmac, not with this module.
+        // TODO: replace them with the hashed canonical request and signature published with
+        // the ACS3-HMAC-SHA256 example of Aliyun, which could not be fetched here.
+        let (params, headers) = documented_request();
+        let (canonical_request, signed_headers) =
+            build_canonical_request("POST", "/", &params, &headers, EMPTY_PAYLOAD_SHA256);
+
+        assert_eq!(
+            build_string_to_sign_v3(&canonical_request),
+            "ACS3-HMAC-SHA256\n\
+             07cf84c506c032076b282686fe2b6706ce71b65c36660cb746cac165b5ae8900"
+        );
+        let signature = calculate_signature_v3(&canonical_request, "YourAccessKeySecret");
+        assert_eq!(
+            signature,
+            "e9eede46fdb0315f85b035fd95099e805ef0e2c6ff82efd02357bb6b9b02742a"
+        );
+        assert_eq!(
+            build_authorization_v3("YourAccessKeyId", &signed_headers, &signature),
+            "ACS3-HMAC-SHA256 Credential=YourAccessKeyId,\
+             SignedHeaders=host;x-acs-action;x-acs-content-sha256;x-acs-date;x-acs-signature-nonce;x-acs-version,\
+             Signature=e9eede46fdb0315f85b035fd95099e805ef0e2c6ff82efd02357bb6b9b02742a"
+        );
+    }
 }
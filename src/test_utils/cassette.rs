@@ -0,0 +1,357 @@
+//! A record/replay ("cassette") transport, to run the tests of the services offline.
+//!
+//! In record mode (`ALIYUN_CASSETTE_MODE=record`) the requests are sent to Aliyun with
+//! the `TEST_SECRETS`, and every request/response pair is written to
+//! `tests/cassettes/<name>.json`. The credentials, signatures, nonces and timestamps are
+//! scrubbed from the requests, and the credentials returned by STS from the responses.
+//!
+//! In replay mode (the default) the responses are served from the cassette, matching the
+//! requests on their method, URL and parameters but not on the volatile parameters.
+//!
+//! The fixtures of `tests/fixtures/hand_written`, replayed by `hand_written_client`, use
+//! the same format but were written by hand after the documented responses; they check
+//! the parsing of the client, not its agreement with Aliyun.
+//!
+//! ```ignore
+//! let client = cassette_client("ecs_describe_regions");
+//! let result = describe_regions(&client, Some("cn-hangzhou")).await;
+//! ```
+
+use std::{
+    collections::BTreeMap,
+    env, fs,
+    path::{Path, PathBuf},
+    sync::{Arc, Mutex},
+};
+
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+
+use crate::client::{
+    error::AdvancedClientError,
+    transport::{HttpRequest, HttpResponse, HttpTransport, ReqwestTransport},
+    AliyunClient,
+};
+use crate::test_utils::TEST_SECRETS;
+
+/// The environment variable selecting the mode, `record` or `replay`.
+pub const CASSETTE_MODE_VAR: &str = "ALIYUN_CASSETTE_MODE";
+
+/// The query parameters differing between two sends of the same request.
+const VOLATILE_PARAMS: &[&str] = &[
+    "AccessKeyId",
+    "Signature",
+    "SignatureNonce",
+    "Timestamp",
+    "SecurityToken",
+];
+
+/// The headers kept in the cassettes; the others are signatures, nonces or dates.
+const RECORDED_HEADERS: &[&str] = &["x-acs-action", "x-acs-version"];
+
+/// The fields of the response bodies holding credentials.
+const SECRET_FIELDS: &[&str] = &["AccessKeyId", "AccessKeySecret", "SecurityToken"];
+
+const SCRUBBED: &str = "SCRUBBED";
+
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+struct RecordedRequest {
+    method: String,
+    /// The URL without the query string.
+    url: String,
+    params: BTreeMap<String, String>,
+    headers: BTreeMap<String, String>,
+}
+
+impl RecordedRequest {
+    fn scrubbed(request: &HttpRequest) -> Self {
+        let mut params = request.query_params();
+        params.retain(|name, _| !VOLATILE_PARAMS.contains(&name.as_str()));
+        let headers = request
+            .headers
+            .iter()
+            .map(|(name, value)| (name.to_lowercase(), value.clone()))
+            .filter(|(name, _)| RECORDED_HEADERS.contains(&name.as_str()))
+            .collect();
+        let url = request.url.split('?').next().unwrap_or_default();
+        RecordedRequest {
+            method: request.method.clone(),
+            url: url.trim_end_matches('/').to_string(),
+            params,
+            headers,
+        }
+    }
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+struct RecordedResponse {
+    status: u16,
+    body: String,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+struct Interaction {
+    request: RecordedRequest,
+    response: RecordedResponse,
+}
+
+#[derive(Serialize, Deserialize, Debug, Default)]
+struct CassetteFile {
+    interactions: Vec<Interaction>,
+}
+
+/// Replaces the credentials of a JSON body, e.g. returned by `AssumeRole`.
+fn scrub_body(body: &str) -> String {
+    fn scrub(value: &mut Value) {
+        match value {
+            Value::Object(map) => {
+                for (name, value) in map.iter_mut() {
+                    if SECRET_FIELDS.contains(&name.as_str()) && value.is_string() {
+                        *value = Value::String(SCRUBBED.to_string());
+                    } else {
+                        scrub(value);
+                    }
+                }
+            }
+            Value::Array(values) => values.iter_mut().for_each(scrub),
+            _ => {}
+        }
+    }
+    match serde_json::from_str::<Value>(body) {
+        Ok(mut value) => {
+            scrub(&mut value);
+            value.to_string()
+        }
+        Err(_) => body.to_string(),
+    }
+}
+
+enum Mode {
+    Record {
+        inner: Arc<dyn HttpTransport>,
+        recorded: Mutex<Vec<Interaction>>,
+    },
+    /// The interactions not replayed yet.
+    Replay { remaining: Mutex<Vec<Interaction>> },
+}
+
+/// An `HttpTransport` recording to or replaying from a cassette file.
+pub struct Cassette {
+    path: PathBuf,
+    mode: Mode,
+}
+
+impl Cassette {
+    /// Sends the requests through `inner` and writes the interactions to `path` after
+    /// every request.
+    pub fn record(path: impl Into<PathBuf>, inner: impl HttpTransport + 'static) -> Self {
+        Self {
+            path: path.into(),
+            mode: Mode::Record {
+                inner: Arc::new(inner),
+                recorded: Mutex::new(Vec::new()),
+            },
+        }
+    }
+
+    /// Serves the responses recorded in `path`.
+    ///
+    /// # Panics
+    ///
+    /// When the cassette cannot be read.
+    pub fn replay(path: impl Into<PathBuf>) -> Self {
+        let path = path.into();
+        let content = fs::read_to_string(&path).unwrap_or_else(|e| {
+            panic!(
+                "cannot read the cassette {} ({}); record it with {}=record",
+                path.display(),
+                e,
+                CASSETTE_MODE_VAR
+            )
+        });
+        let file: CassetteFile = serde_json::from_str(&content)
+            .unwrap_or_else(|e| panic!("invalid cassette {}: {}", path.display(), e));
+        Self {
+            path,
+            mode: Mode::Replay {
+                remaining: Mutex::new(file.interactions),
+            },
+        }
+    }
+
+    /// Whether the tests record their cassettes, from `ALIYUN_CASSETTE_MODE`.
+    pub fn is_recording() -> bool {
+        env::var(CASSETTE_MODE_VAR).is_ok_and(|mode| mode.eq_ignore_ascii_case("record"))
+    }
+
+    /// The path of the cassette `name` in `tests/cassettes`.
+    pub fn path_of(name: &str) -> PathBuf {
+        Path::new(env!("CARGO_MANIFEST_DIR"))
+            .join("tests")
+            .join("cassettes")
+            .join(format!("{}.json", name))
+    }
+
+    /// The path of the hand-written fixture `name` in `tests/fixtures/hand_written`.
+    pub fn hand_written_path_of(name: &str) -> PathBuf {
+        Path::new(env!("CARGO_MANIFEST_DIR"))
+            .join("tests")
+            .join("fixtures")
+            .join("hand_written")
+            .join(format!("{}.json", name))
+    }
+
+    fn save(&self, interactions: &[Interaction]) {
+        if let Some(parent) = self.path.parent() {
+            fs::create_dir_all(parent).unwrap();
+        }
+        let file = CassetteFile {
+            interactions: interactions.to_vec(),
+        };
+        fs::write(&self.path, serde_json::to_string_pretty(&file).unwrap()).unwrap();
+    }
+}
+
+#[async_trait::async_trait]
+impl HttpTransport for Cassette {
+    /// # Panics
+    ///
+    /// In replay mode, when no recorded interaction matches the request.
+    async fn send(&self, request: HttpRequest) -> Result<HttpResponse, AdvancedClientError> {
+        let recorded_request = RecordedRequest::scrubbed(&request);
+        match &self.mode {
+            Mode::Record { inner, recorded } => {
+                let response = inner.send(request).await?;
+                let mut recorded = recorded.lock().unwrap();
+                recorded.push(Interaction {
+                    request: recorded_request,
+                    response: RecordedResponse {
+                        status: response.status,
+                        body: scrub_body(&response.body),
+                    },
+                });
+                self.save(&recorded);
+                Ok(response)
+            }
+            Mode::Replay { remaining } => {
+                let mut remaining = remaining.lock().unwrap();
+                let index = remaining
+                    .iter()
+                    .position(|interaction| interaction.request == recorded_request)
+                    .unwrap_or_else(|| {
+                        panic!(
+                            "no interaction of the cassette {} matches {:?}",
+                            self.path.display(),
+                            recorded_request
+                        )
+                    });
+                let interaction = remaining.remove(index);
+                Ok(HttpResponse::new(
+                    interaction.response.status,
+                    interaction.response.body,
+                ))
+            }
+        }
+    }
+}
+
+/// A client recording to or replaying from the cassette `name`, see the module docs.
+///
+/// It uses the `TEST_SECRETS` in record mode and dummy credentials in replay mode.
+pub fn cassette_client(name: &str) -> AliyunClient {
+    let path = Cassette::path_of(name);
+    if Cassette::is_recording() {
+        let client = AliyunClient::builder()
+            .credentials(
+                TEST_SECRETS.access_key_id.clone(),
+                TEST_SECRETS.access_key_secret.clone(),
+            )
+            .build()
+            .unwrap();
+        let inner = ReqwestTransport::new(client.http_client().clone());
+        client.with_transport(Cassette::record(path, inner))
+    } else {
+        AliyunClient::builder()
+            .credentials("testid".to_string(), "testsecret".to_string())
+            .transport(Cassette::replay(path))
+            .build()
+            .unwrap()
+    }
+}
+
+/// A client replaying the hand-written fixture `name`, see the module docs.
+pub fn hand_written_client(name: &str) -> AliyunClient {
+    AliyunClient::builder()
+        .credentials("testid".to_string(), "testsecret".to_string())
+        .transport(Cassette::replay(Cassette::hand_written_path_of(name)))
+        .build()
+        .unwrap()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    use crate::client::transport::MockTransport;
+
+    fn params() -> BTreeMap<String, String> {
+        BTreeMap::from([
+            ("Action".to_string(), "AssumeRole".to_string()),
+            ("Version".to_string(), "2015-04-01".to_string()),
+        ])
+    }
+
+    #[tokio::test]
+    async fn test_record_then_replay() {
+        let path = env::temp_dir().join(format!("cassette-{}.json", uuid::Uuid::new_v4()));
+        let aliyun = MockTransport::new();
+        aliyun.push_response(HttpResponse::json(&json!({
+            "RequestId": "REQ",
+            "Credentials": {"AccessKeyId": "STS.id", "AccessKeySecret": "secret", "SecurityToken": "token"}
+        })));
+
+        let client = AliyunClient::builder()
+            .credentials("realid".to_string(), "realsecret".to_string())
+            .transport(Cassette::record(&path, aliyun))
+            .build()
+            .unwrap();
+        client
+            .send_request("sts.aliyuncs.com", params())
+            .await
+            .unwrap();
+
+        let content = fs::read_to_string(&path).unwrap();
+        for secret in ["realid", "realsecret", "STS.id", "token", "SignatureNonce"] {
+            assert!(!content.contains(secret), "{} was recorded", secret);
+        }
+
+        let client = AliyunClient::builder()
+            .credentials("testid".to_string(), "testsecret".to_string())
+            .transport(Cassette::replay(&path))
+            .build()
+            .unwrap();
+        let body = client
+            .send_request("sts.aliyuncs.com", params())
+            .await
+            .unwrap();
+        assert_eq!(body["RequestId"], "REQ");
+        assert_eq!(body["Credentials"]["AccessKeySecret"], SCRUBBED);
+        fs::remove_file(&path).unwrap();
+    }
+
+    #[tokio::test]
+    #[should_panic(expected = "no interaction")]
+    async fn test_replay_rejects_unknown_requests() {
+        let path = env::temp_dir().join(format!("cassette-{}.json", uuid::Uuid::new_v4()));
+        fs::write(&path, r#"{"interactions": []}"#).unwrap();
+        let transport = Cassette::replay(&path);
+        fs::remove_file(&path).unwrap();
+        let client = AliyunClient::builder()
+            .credentials("testid".to_string(), "testsecret".to_string())
+            .transport(transport)
+            .build()
+            .unwrap();
+        let _ = client.send_request("sts.aliyuncs.com", params()).await;
+    }
+}
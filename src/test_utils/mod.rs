@@ -1,4 +1,6 @@
+pub mod cassette;
 pub mod error;
+pub mod http_stub;
 
 pub use error::TestSecretsError;
 
@@ -6,6 +8,7 @@ use once_cell::sync::Lazy;
 use std::env;
 
 use crate::client::AliyunClient;
+use crate::mock_server::MockAliyunServer;
 
 pub struct TestSecrets {
     pub access_key_id: String,
@@ -88,6 +91,15 @@ pub fn create_aliyun_client<P: ClientCredentialProvider>() -> AliyunClient {
     AliyunClient::new(credentials.0, credentials.1)
 }
 
+/// Creates a client sending its requests to a `MockAliyunServer`, which rejects `EMPTY`
+/// and `INVALID` credentials like Aliyun.
+pub fn create_mock_aliyun_client<P: ClientCredentialProvider>(
+    server: &MockAliyunServer,
+) -> AliyunClient {
+    let credentials = P::get_credentials();
+    server.client(&credentials.0, &credentials.1)
+}
+
 /// Macro to test multiple credential providers that should fail.
 /// 
 /// # Example
@@ -103,8 +115,24 @@ pub fn create_aliyun_client<P: ClientCredentialProvider>() -> AliyunClient {
 ///     }
 /// }
 /// ```
+///
+/// With `server: <MockAliyunServer>` first, the clients send their requests to the mock
+/// server instead of Aliyun, so the test runs offline.
 #[macro_export]
 macro_rules! test_multiple_clients {
+    (server: $server:expr, [$($provider:ty => $name:expr),* $(,)?], |$client:ident, $name_var:ident| $test_fn:expr) => {
+        {
+            let server = &$server;
+            $(
+                {
+                    let $client = $crate::test_utils::create_mock_aliyun_client::<$provider>(server);
+                    let $name_var = $name;
+                    let test_closure = $test_fn;
+                    test_closure.await;
+                }
+            )*
+        }
+    };
     ([$($provider:ty => $name:expr),* $(,)?], |$client:ident, $name_var:ident| $test_fn:expr) => {
         $(
             {
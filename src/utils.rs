@@ -1,4 +1,4 @@
-use percent_encoding::{percent_encode, AsciiSet, NON_ALPHANUMERIC};
+use percent_encoding::{percent_decode_str, percent_encode, AsciiSet, NON_ALPHANUMERIC};
 
 /// Encodes the given string using Aliyun's percent encoding rules,
 /// preserving unreserved characters (A-Z, a-z, 0-9, '-', '_', '.', '~').
@@ -20,3 +20,112 @@ pub fn build_canonicalized_query(params: &std::collections::BTreeMap<String, Str
         .collect::<Vec<String>>()
         .join("&")
 }
+
+/// Parses a query string (without the leading '?') into its percent-decoded parameters.
+pub fn parse_query(query: &str) -> std::collections::BTreeMap<String, String> {
+    let decode = |component: &str| {
+        percent_decode_str(component)
+            .decode_utf8_lossy()
+            .into_owned()
+    };
+    query
+        .split('&')
+        .filter(|pair| !pair.is_empty())
+        .map(|pair| {
+            let (name, value) = pair.split_once('=').unwrap_or((pair, ""));
+            (decode(name), decode(value))
+        })
+        .collect()
+}
+
+/// Encodes the given bytes as a lowercase hexadecimal string.
+pub fn hex_encode(bytes: &[u8]) -> String {
+    bytes.iter().map(|b| format!("{:02x}", b)).collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use proptest::prelude::*;
+    use std::collections::BTreeMap;
+
+    fn is_unreserved(byte: u8) -> bool {
+        byte.is_ascii_alphanumeric() || b"-_.~".contains(&byte)
+    }
+
+    #[test]
+    fn test_percent_encode() {
+        assert_eq!(aliyun_percent_encode("AZaz09-_.~"), "AZaz09-_.~");
+        assert_eq!(aliyun_percent_encode("a b"), "a%20b");
+        assert_eq!(aliyun_percent_encode("a+b"), "a%2Bb");
+        assert_eq!(aliyun_percent_encode("*"), "%2A");
+        assert_eq!(aliyun_percent_encode("/?&=:%"), "%2F%3F%26%3D%3A%25");
+        assert_eq!(aliyun_percent_encode(""), "");
+        assert_eq!(aliyun_percent_encode("华东1"), "%E5%8D%8E%E4%B8%9C1");
+        assert_eq!(aliyun_percent_encode("é"), "%C3%A9");
+    }
+
+    #[test]
+    fn test_canonicalized_query() {
+        let params = BTreeMap::from(
+            [
+                ("Tag.2.Key", "b"),
+                ("Tag.10.Key", "c"),
+                ("Tag.1.Key", "a"),
+                ("Empty", ""),
+                ("Name", "Zhang San"),
+            ]
+            .map(|(k, v)| (k.to_string(), v.to_string())),
+        );
+        // Sorted by byte order of the names, not numerically.
+        assert_eq!(
+            build_canonicalized_query(&params),
+            "Empty=&Name=Zhang%20San&Tag.1.Key=a&Tag.10.Key=c&Tag.2.Key=b"
+        );
+        assert_eq!(build_canonicalized_query(&BTreeMap::new()), "");
+    }
+
+    #[test]
+    fn test_parse_query() {
+        let params = parse_query("Name=Zhang%20San&Empty=&Flag&Plus=a%2Bb");
+        assert_eq!(params["Name"], "Zhang San");
+        assert_eq!(params["Empty"], "");
+        assert_eq!(params["Flag"], "");
+        assert_eq!(params["Plus"], "a+b");
+    }
+
+    proptest! {
+        /// RFC 3986: unreserved characters are kept, every other byte becomes `%XX` with
+        /// uppercase hexadecimal digits.
+        #[test]
+        fn prop_percent_encode_follows_rfc3986(input in any::<String>()) {
+            let expected: String = input
+                .bytes()
+                .map(|byte| {
+                    if is_unreserved(byte) {
+                        (byte as char).to_string()
+                    } else {
+                        format!("%{:02X}", byte)
+                    }
+                })
+                .collect();
+            prop_assert_eq!(aliyun_percent_encode(&input), expected);
+        }
+
+        #[test]
+        fn prop_percent_encode_round_trips(input in any::<String>()) {
+            let encoded = aliyun_percent_encode(&input);
+            prop_assert!(encoded.bytes().all(|b| is_unreserved(b) || b == b'%'));
+            let decoded = percent_decode_str(&encoded).decode_utf8().unwrap();
+            prop_assert_eq!(decoded, input);
+        }
+
+        #[test]
+        fn prop_canonicalized_query_round_trips(
+            params in proptest::collection::btree_map(".+", ".*", 0..8)
+        ) {
+            let query = build_canonicalized_query(&params);
+            prop_assert_eq!(parse_query(&query), params);
+        }
+    }
+}